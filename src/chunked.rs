@@ -0,0 +1,320 @@
+use crate::datatype::ScalarValue;
+use crate::ops::concat::concat_with_type;
+use crate::{as_array, Field};
+use arrow::array::{Array, ArrayRef, BinaryArray, BooleanArray, PrimitiveArray};
+use arrow::datatypes::{self as dt, DataType};
+use snafu::Snafu;
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("Chunk has the data type {:?}, expected {:?}", data_type, expected))]
+    UnequalDataTypes {
+        data_type: DataType,
+        expected: DataType,
+    },
+    #[snafu(display(
+        "Fields must have the same names and data types, lhs {:?} rhs {:?}",
+        lhs,
+        rhs
+    ))]
+    UnequalFields { lhs: Vec<Field>, rhs: Vec<Field> },
+}
+
+macro_rules! scalar_value {
+    ($arr:ident, $row:ident, $( [$dt:path, $ty:ty, $variant:ident] ),*) => {
+        match $arr.data_type() {
+            $($dt => Ok(ScalarValue::$variant(as_array!($arr, PrimitiveArray<$ty>)?.value($row))),)+
+            DataType::Boolean => Ok(ScalarValue::Boolean(as_array!($arr, BooleanArray)?.value($row))),
+            DataType::Utf8 => Ok(ScalarValue::String(as_array!($arr, BinaryArray)?.value($row).into())),
+            data_type => Err(crate::Error::UnknownDataType {
+                data_type: data_type.clone(),
+            }),
+        }
+    };
+}
+
+/// A column stored as a list of arrays which share a data type, appending adds a chunk instead of
+/// copying the existing values
+#[derive(Clone, Debug)]
+pub struct ChunkedArray {
+    data_type: DataType,
+    chunks: Vec<ArrayRef>,
+    /// the row each chunk starts at
+    offsets: Vec<usize>,
+    len: usize,
+}
+
+impl ChunkedArray {
+    pub fn new(data_type: DataType, chunks: Vec<ArrayRef>) -> Result<ChunkedArray, crate::Error> {
+        let mut arr = ChunkedArray {
+            data_type,
+            chunks: Vec::with_capacity(chunks.len()),
+            offsets: Vec::with_capacity(chunks.len()),
+            len: 0,
+        };
+
+        for chunk in chunks {
+            arr.append(chunk)?;
+        }
+
+        Ok(arr)
+    }
+
+    pub fn data_type(&self) -> &DataType {
+        &self.data_type
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn null_count(&self) -> usize {
+        self.chunks.iter().map(|chunk| chunk.null_count()).sum()
+    }
+
+    pub fn num_chunks(&self) -> usize {
+        self.chunks.len()
+    }
+
+    pub fn chunks(&self) -> &Vec<ArrayRef> {
+        &self.chunks
+    }
+
+    /// Add the array as the last chunk, it must have the same data type
+    pub fn append(&mut self, chunk: ArrayRef) -> Result<(), crate::Error> {
+        if chunk.data_type() != &self.data_type {
+            return Err(Error::UnequalDataTypes {
+                data_type: chunk.data_type().clone(),
+                expected: self.data_type.clone(),
+            }
+            .into());
+        }
+
+        // empty chunks are dropped so that each offset starts a different row, an empty array
+        // keeps a single empty chunk
+        if self.len == 0 {
+            self.chunks.clear();
+            self.offsets.clear();
+        } else if chunk.is_empty() {
+            return Ok(());
+        }
+
+        self.offsets.push(self.len);
+        self.len += chunk.len();
+        self.chunks.push(chunk);
+
+        Ok(())
+    }
+
+    /// Append the chunks of the other array
+    pub fn extend(&mut self, other: &ChunkedArray) -> Result<(), crate::Error> {
+        for chunk in other.chunks() {
+            self.append(chunk.clone())?;
+        }
+        Ok(())
+    }
+
+    /// The values as a single array, a copy is only made when there are multiple chunks
+    pub fn to_array(&self) -> Result<ArrayRef, crate::Error> {
+        match self.chunks.as_slice() {
+            [chunk] => Ok(chunk.clone()),
+            chunks => concat_with_type(&self.data_type, chunks),
+        }
+    }
+
+    /// Consolidate the chunks into one
+    pub fn rechunk(&self) -> Result<ChunkedArray, crate::Error> {
+        Ok(ChunkedArray::from(self.to_array()?))
+    }
+
+    /// Find the chunk holding the row and the position of the row within that chunk
+    fn locate(&self, row: usize) -> Option<(usize, usize)> {
+        if row >= self.len {
+            return None;
+        }
+
+        // the offsets are sorted and unique, so the chunk is the last one starting at or before
+        // the row
+        let chunk = match self.offsets.binary_search(&row) {
+            Ok(chunk) => chunk,
+            Err(chunk) => chunk - 1,
+        };
+
+        Some((chunk, row - self.offsets[chunk]))
+    }
+
+    pub fn is_null(&self, row: usize) -> bool {
+        match self.locate(row) {
+            Some((chunk, i)) => self.chunks[chunk].is_null(i),
+            None => true,
+        }
+    }
+
+    /// The value of the row, null values are `ScalarValue::Null`
+    pub fn value_scalar(&self, row: usize) -> Result<ScalarValue<'_>, crate::Error> {
+        let (chunk, row) = self.locate(row).ok_or(crate::Error::IndexOutOfBounds {
+            index: row,
+            len: self.len,
+        })?;
+
+        let arr = &self.chunks[chunk];
+
+        if arr.is_null(row) {
+            return Ok(ScalarValue::Null);
+        }
+
+        scalar_value!(
+            arr,
+            row,
+            [DataType::UInt8, dt::UInt8Type, UInt8],
+            [DataType::UInt16, dt::UInt16Type, UInt16],
+            [DataType::UInt32, dt::UInt32Type, UInt32],
+            [DataType::UInt64, dt::UInt64Type, UInt64],
+            [DataType::Int8, dt::Int8Type, Int8],
+            [DataType::Int16, dt::Int16Type, Int16],
+            [DataType::Int32, dt::Int32Type, Int32],
+            [DataType::Int64, dt::Int64Type, Int64],
+            [DataType::Float32, dt::Float32Type, Float32],
+            [DataType::Float64, dt::Float64Type, Float64]
+        )
+    }
+}
+
+/// Split the columns at every row where any of them starts a chunk, so the pieces line up across
+/// the columns. The pieces are slices of the chunks, no values are copied.
+pub fn align_chunks(columns: &[&ChunkedArray]) -> Vec<Vec<ArrayRef>> {
+    let len = columns.first().map_or(0, |arr| arr.len());
+
+    let mut bounds: Vec<usize> = columns
+        .iter()
+        .flat_map(|arr| arr.offsets.iter().copied())
+        .chain(std::iter::once(len))
+        .collect();
+    bounds.sort_unstable();
+    bounds.dedup();
+
+    bounds
+        .windows(2)
+        .map(|window| {
+            let (start, len) = (window[0], window[1] - window[0]);
+
+            columns
+                .iter()
+                .map(|arr| {
+                    // every chunk start is a bound, so the piece lies within a single chunk
+                    let (chunk, i) = arr.locate(start).expect("bounds are within the columns");
+                    let chunk = &arr.chunks[chunk];
+
+                    if i == 0 && len == chunk.len() {
+                        chunk.clone()
+                    } else {
+                        chunk.slice(i, len)
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+impl From<ArrayRef> for ChunkedArray {
+    fn from(arr: ArrayRef) -> ChunkedArray {
+        ChunkedArray {
+            data_type: arr.data_type().clone(),
+            offsets: vec![0],
+            len: arr.len(),
+            chunks: vec![arr],
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_chunked {
+    use super::*;
+    use arrow::array::{BinaryBuilder, Int32Array};
+    use std::sync::Arc;
+
+    fn chunk(values: Vec<Option<i32>>) -> ArrayRef {
+        Arc::new(Int32Array::from(values))
+    }
+
+    #[test]
+    fn it_appends_chunks() {
+        let mut arr = ChunkedArray::from(chunk(vec![Some(1), None]));
+        arr.append(chunk(vec![])).unwrap();
+        arr.append(chunk(vec![Some(3), Some(4), Some(5)])).unwrap();
+
+        assert_eq!(arr.len(), 5);
+        assert_eq!(arr.num_chunks(), 2);
+        assert_eq!(arr.null_count(), 1);
+
+        assert_eq!(arr.value_scalar(0).unwrap(), ScalarValue::Int32(1));
+        assert_eq!(arr.value_scalar(1).unwrap(), ScalarValue::Null);
+        assert_eq!(arr.value_scalar(2).unwrap(), ScalarValue::Int32(3));
+        assert_eq!(arr.value_scalar(4).unwrap(), ScalarValue::Int32(5));
+        assert!(arr.value_scalar(5).is_err());
+
+        let rechunked = arr.rechunk().unwrap();
+        assert_eq!(rechunked.num_chunks(), 1);
+
+        let values = rechunked.to_array().unwrap();
+        assert_eq!(
+            as_array!(values, Int32Array).unwrap(),
+            &Int32Array::from(vec![Some(1), None, Some(3), Some(4), Some(5)])
+        );
+    }
+
+    #[test]
+    fn it_aligns_the_chunks_of_columns() {
+        let lhs = ChunkedArray::new(
+            DataType::Int32,
+            vec![chunk(vec![Some(1), Some(2), Some(3)]), chunk(vec![Some(4)])],
+        )
+        .unwrap();
+        let rhs = ChunkedArray::new(
+            DataType::Int32,
+            vec![chunk(vec![Some(5)]), chunk(vec![Some(6), None, Some(8)])],
+        )
+        .unwrap();
+
+        let pieces = align_chunks(&[&lhs, &rhs]);
+        let lengths: Vec<usize> = pieces.iter().map(|piece| piece[0].len()).collect();
+        assert_eq!(lengths, vec![1, 2, 1]);
+
+        let (lhs_piece, rhs_piece) = (&pieces[1][0], &pieces[1][1]);
+
+        let lhs_piece = as_array!(lhs_piece, Int32Array).unwrap();
+        assert_eq!(lhs_piece.value(0), 2);
+        assert_eq!(lhs_piece.value(1), 3);
+
+        let rhs_piece = as_array!(rhs_piece, Int32Array).unwrap();
+        assert_eq!(rhs_piece.value(0), 6);
+        assert!(rhs_piece.is_null(1));
+    }
+
+    #[test]
+    fn it_rejects_chunks_of_another_type() {
+        let mut arr = ChunkedArray::from(chunk(vec![Some(1)]));
+
+        let mut builder = BinaryBuilder::new(1);
+        builder.append_value(b"a").unwrap();
+
+        assert!(arr.append(Arc::new(builder.finish())).is_err());
+        assert_eq!(arr.len(), 1);
+    }
+
+    #[test]
+    fn it_reads_string_values() {
+        let mut builder = BinaryBuilder::new(2);
+        builder.append_value(b"a").unwrap();
+        builder.append_null().unwrap();
+
+        let arr = ChunkedArray::new(DataType::Utf8, vec![Arc::new(builder.finish())]).unwrap();
+
+        assert_eq!(arr.value_scalar(0).unwrap().as_string().unwrap(), "a");
+        assert!(arr.is_null(1));
+    }
+}
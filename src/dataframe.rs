@@ -1,7 +1,15 @@
-use crate::query::select::Select;
+use crate::column::Column;
+use crate::datasource::csv::{CsvWriteOptions, CsvWriter};
+use crate::datasource::ipc::{IpcFormat, IpcWriter};
+use crate::datasource::json::{JsonWriteOptions, JsonWriter};
+#[cfg(feature = "parquet")]
+use crate::datasource::parquet::{ParquetWriteOptions, ParquetWriter};
+use crate::query::join::{self, JoinType};
+use crate::query::select::{Aggregate, Select};
 use crate::query::Query;
 use crate::view::View;
 use crate::Error;
+use std::io::Write;
 
 #[derive(Clone, Debug)]
 pub struct DataFrame {
@@ -21,7 +29,64 @@ impl DataFrame {
         Ok(Query::new(self.view).select(columns).exec()?.to_df())
     }
 
+    /// Reduce whole columns into a view with a single row, see `View::agg`
+    pub fn agg(&self, aggregates: Vec<(Aggregate, Column)>) -> Result<View, Error> {
+        self.view.agg(aggregates)
+    }
+
+    /// Join with another data frame on pairs of (self, other) key columns, see `join::join`
+    pub fn join(
+        &self,
+        other: &DataFrame,
+        on: Vec<(Column, Column)>,
+        join_type: JoinType,
+    ) -> Result<DataFrame, Error> {
+        Ok(join::join(&self.view, &other.view, on, join_type)?.to_df())
+    }
+
     pub fn view(&self) -> &View {
         &self.view
     }
+
+    /// Append the rows of the other data frame as new chunks, see `View::append`
+    pub fn append(&mut self, other: &DataFrame) -> Result<(), Error> {
+        self.view.append(&other.view)
+    }
+
+    /// Consolidate the chunks of every column, see `View::rechunk`
+    pub fn rechunk(&mut self) -> Result<(), Error> {
+        self.view.rechunk()
+    }
+
+    /// Write the rows as a CSV, e.g. to a file or a `Vec<u8>`
+    pub fn to_csv<W: Write>(&self, writer: W, options: CsvWriteOptions) -> Result<(), Error> {
+        CsvWriter::with_options(options).write(&self.view, writer)
+    }
+
+    pub fn to_csv_path(&self, path: &str, options: CsvWriteOptions) -> Result<(), Error> {
+        CsvWriter::with_options(options).write_path(&self.view, path)
+    }
+
+    /// Write the rows as JSON records or newline-delimited JSON
+    pub fn to_json<W: Write>(&self, writer: W, options: JsonWriteOptions) -> Result<(), Error> {
+        JsonWriter::with_options(options).write(&self.view, writer)
+    }
+
+    pub fn to_json_path(&self, path: &str, options: JsonWriteOptions) -> Result<(), Error> {
+        JsonWriter::with_options(options).write_path(&self.view, path)
+    }
+
+    /// Write the columns in the arrow IPC file or stream format, see `IpcWriter::write`
+    pub fn to_ipc<W: Write>(&self, writer: W, format: IpcFormat) -> Result<(), Error> {
+        IpcWriter::write(&self.view, writer, format)
+    }
+
+    pub fn to_ipc_path(&self, path: &str, format: IpcFormat) -> Result<(), Error> {
+        IpcWriter::write_path(&self.view, path, format)
+    }
+
+    #[cfg(feature = "parquet")]
+    pub fn to_parquet_path(&self, path: &str, options: ParquetWriteOptions) -> Result<(), Error> {
+        ParquetWriter::with_options(options).write_path(&self.view, path)
+    }
 }
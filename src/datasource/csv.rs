@@ -1,53 +1,765 @@
-use crate::{DataFrame, Error, View};
-use arrow::array::ArrayRef;
+use crate::datatype::ScalarValue;
+use crate::{ChunkedArray, DataFrame, View};
+use arrow::array::{Array, ArrayRef, BinaryArray, BinaryBuilder};
+use arrow::datatypes::{DataType, Schema, SchemaRef};
+use snafu::Snafu;
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::io::{Cursor, Read, Seek, Write};
+use std::sync::Arc;
 
-pub struct CsvReader {}
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("Failed to parse the CSV: {}", source))]
+    Parse { source: ::csv::Error },
+
+    #[snafu(display("Failed to write the normalized CSV: {}", source))]
+    Normalize { source: ::csv::Error },
+
+    #[snafu(display("Failed to write the CSV: {}", source))]
+    Write { source: ::csv::Error },
+}
+
+/// How the values of a CSV are parsed
+#[derive(Clone, Debug)]
+pub struct CsvReadOptions {
+    delimiter: u8,
+    quote: u8,
+    has_headers: bool,
+    schema: Option<SchemaRef>,
+    infer_schema_rows: Option<usize>,
+    projection: Option<Vec<usize>>,
+    batch_size: usize,
+    null_values: Vec<String>,
+    skip_rows: usize,
+}
+
+impl Default for CsvReadOptions {
+    fn default() -> CsvReadOptions {
+        CsvReadOptions {
+            delimiter: b',',
+            quote: b'"',
+            has_headers: true,
+            schema: None,
+            infer_schema_rows: Some(20),
+            projection: None,
+            batch_size: 1024,
+            null_values: vec![],
+            skip_rows: 0,
+        }
+    }
+}
+
+impl CsvReadOptions {
+    pub fn new() -> CsvReadOptions {
+        CsvReadOptions::default()
+    }
+
+    pub fn delimiter(mut self, delimiter: u8) -> CsvReadOptions {
+        self.delimiter = delimiter;
+        self
+    }
+
+    pub fn quote(mut self, quote: u8) -> CsvReadOptions {
+        self.quote = quote;
+        self
+    }
+
+    /// Whether the first row, after the skipped rows, holds the column names
+    pub fn has_headers(mut self, has_headers: bool) -> CsvReadOptions {
+        self.has_headers = has_headers;
+        self
+    }
+
+    /// Use the schema instead of inferring one from the data
+    pub fn schema(mut self, schema: Schema) -> CsvReadOptions {
+        self.schema = Some(Arc::new(schema));
+        self
+    }
+
+    /// Rows scanned for the schema, `None` scans them all
+    pub fn infer_schema_rows(mut self, rows: Option<usize>) -> CsvReadOptions {
+        self.infer_schema_rows = rows;
+        self
+    }
+
+    /// Only read the columns at the indexes, in the given order
+    pub fn projection(mut self, projection: Vec<usize>) -> CsvReadOptions {
+        self.projection = Some(projection);
+        self
+    }
+
+    /// The number of rows parsed at a time
+    pub fn batch_size(mut self, batch_size: usize) -> CsvReadOptions {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Values which are read as null, e.g. `NA` or `NULL`. Empty values are always null in
+    /// numeric columns.
+    pub fn null_values(mut self, null_values: Vec<&str>) -> CsvReadOptions {
+        self.null_values = null_values.into_iter().map(|v| v.to_string()).collect();
+        self
+    }
+
+    /// Skip rows at the start of the file, before the headers
+    pub fn skip_rows(mut self, skip_rows: usize) -> CsvReadOptions {
+        self.skip_rows = skip_rows;
+        self
+    }
+
+    /// arrow only understands the delimiter, the other options require the data to be rewritten
+    /// first
+    fn needs_normalizing(&self) -> bool {
+        self.quote != b'"' || !self.null_values.is_empty() || self.skip_rows > 0
+    }
+}
+
+/// The rewritten CSV along with the (row, column) of every value that matched a null value. The
+/// rows exclude the headers.
+struct Normalized {
+    data: Vec<u8>,
+    nulls: HashSet<(usize, usize)>,
+}
+
+pub struct CsvReader {
+    options: CsvReadOptions,
+}
 
 impl CsvReader {
-    pub fn from_path(path: &str) -> Result<DataFrame, Error> {
+    pub fn from_path(path: &str) -> Result<DataFrame, crate::Error> {
+        CsvReader::with_options(CsvReadOptions::default()).read_path(path)
+    }
+
+    pub fn from_reader<R: Read>(reader: R) -> Result<DataFrame, crate::Error> {
+        CsvReader::with_options(CsvReadOptions::default()).read(reader)
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(data: &str) -> Result<DataFrame, crate::Error> {
+        CsvReader::with_options(CsvReadOptions::default()).read_str(data)
+    }
+
+    pub fn with_options(options: CsvReadOptions) -> CsvReader {
+        CsvReader { options }
+    }
+
+    pub fn read_path(&self, path: &str) -> Result<DataFrame, crate::Error> {
         let file = std::fs::File::open(path)?;
+        self.read_seekable(file)
+    }
+
+    /// Read the CSV from any reader, e.g. stdin or a decompressed stream. The data is buffered in
+    /// memory since the schema inference must seek back to the start.
+    pub fn read<R: Read>(&self, mut reader: R) -> Result<DataFrame, crate::Error> {
+        if self.options.needs_normalizing() {
+            return self.read_normalized(reader);
+        }
+
+        let mut data = vec![];
+        reader.read_to_end(&mut data)?;
+
+        self.read_arrow(Cursor::new(data), self.options.delimiter, &HashSet::new())
+    }
 
-        let mut reader = arrow::csv::ReaderBuilder::new()
-            .infer_schema(Some(20))
-            .has_headers(true)
-            .build(file)?;
+    pub fn read_str(&self, data: &str) -> Result<DataFrame, crate::Error> {
+        self.read_seekable(Cursor::new(data.as_bytes()))
+    }
+
+    fn read_seekable<R: Read + Seek>(&self, reader: R) -> Result<DataFrame, crate::Error> {
+        if self.options.needs_normalizing() {
+            return self.read_normalized(reader);
+        }
+
+        self.read_arrow(reader, self.options.delimiter, &HashSet::new())
+    }
+
+    fn read_normalized<R: Read>(&self, reader: R) -> Result<DataFrame, crate::Error> {
+        let normalized = self.normalize(reader)?;
+        self.read_arrow(Cursor::new(normalized.data), b',', &normalized.nulls)
+    }
+
+    /// Rewrite the CSV with the default quoting and comma delimiter, dropping the skipped rows and
+    /// replacing the null values with empty values
+    fn normalize<R: Read>(&self, reader: R) -> Result<Normalized, crate::Error> {
+        let options = &self.options;
+
+        let mut reader = ::csv::ReaderBuilder::new()
+            .delimiter(options.delimiter)
+            .quote(options.quote)
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(reader);
+
+        // ragged rows are kept as they are, so arrow rejects them just as it does unnormalized
+        let mut writer = ::csv::WriterBuilder::new()
+            .flexible(true)
+            .from_writer(vec![]);
+        let mut nulls = HashSet::new();
+
+        let records = reader.byte_records().skip(options.skip_rows);
+        let header_rows = if options.has_headers { 1 } else { 0 };
+
+        for (i, record) in records.enumerate() {
+            let record = record.map_err(|source| Error::Parse { source })?;
+
+            if i < header_rows {
+                writer
+                    .write_byte_record(&record)
+                    .map_err(|source| Error::Normalize { source })?;
+                continue;
+            }
+
+            let row = i - header_rows;
+            let fields = record.iter().enumerate().map(|(column, value)| {
+                if options
+                    .null_values
+                    .iter()
+                    .any(|null| null.as_bytes() == value)
+                {
+                    nulls.insert((row, column));
+                    &[] as &[u8]
+                } else {
+                    value
+                }
+            });
+
+            writer
+                .write_record(fields)
+                .map_err(|source| Error::Normalize { source })?;
+        }
+
+        let data = writer.into_inner().map_err(|err| Error::Normalize {
+            source: err.into_error().into(),
+        })?;
+
+        Ok(Normalized { data, nulls })
+    }
+
+    fn read_arrow<R: Read + Seek>(
+        &self,
+        reader: R,
+        delimiter: u8,
+        nulls: &HashSet<(usize, usize)>,
+    ) -> Result<DataFrame, crate::Error> {
+        let options = &self.options;
+
+        let mut builder = arrow::csv::ReaderBuilder::new()
+            .has_headers(options.has_headers)
+            .with_delimiter(delimiter)
+            .with_batch_size(options.batch_size);
+
+        builder = match &options.schema {
+            Some(schema) => builder.with_schema(schema.clone()),
+            None => builder.infer_schema(options.infer_schema_rows),
+        };
+
+        if let Some(projection) = &options.projection {
+            builder = builder.with_projection(projection.clone());
+        }
+
+        let mut reader = builder.build(reader)?;
 
         let schema = reader.schema();
-        let mut columns: Vec<ArrayRef> = vec![];
+        let fields = match &options.projection {
+            Some(projection) => projection
+                .iter()
+                .map(|i| schema.field(*i).clone())
+                .collect(),
+            None => schema.fields().to_owned(),
+        };
+
+        // each batch holds a slice of the rows for every column, which are kept as the chunks of
+        // the column
+        let mut chunks: Vec<Vec<ArrayRef>> = vec![vec![]; fields.len()];
+        let mut offset = 0;
 
         while let Some(batch) = reader.next()? {
             for idx in 0..batch.num_columns() {
                 let column: ArrayRef = batch.column(idx).to_owned();
-                columns.push(column);
+
+                // empty values are only null in the numeric columns, the strings which matched a
+                // null value must be replaced
+                let source_idx = match &options.projection {
+                    Some(projection) => projection[idx],
+                    None => idx,
+                };
+                let column = null_strings(&column, offset, source_idx, nulls)?;
+
+                chunks[idx].push(column);
             }
+
+            offset += batch.num_rows();
         }
 
-        Ok(DataFrame::new(View::new(
-            schema.fields().to_owned(),
-            columns,
-        )))
+        let columns = fields
+            .iter()
+            .zip(chunks.into_iter())
+            .map(|(field, chunks)| ChunkedArray::new(field.data_type().clone(), chunks))
+            .collect::<Result<Vec<_>, crate::Error>>()?;
+
+        Ok(DataFrame::new(View::from_chunks(fields, columns)))
     }
 }
 
+/// When the values written to a CSV are wrapped in quotes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum QuoteStyle {
+    /// Only values containing the delimiter, a quote or a new line
+    Necessary,
+    Always,
+    /// Every value which is not a number, including the nulls
+    NonNumeric,
+    Never,
+}
+
+impl QuoteStyle {
+    fn as_csv(self) -> ::csv::QuoteStyle {
+        match self {
+            QuoteStyle::Necessary => ::csv::QuoteStyle::Necessary,
+            QuoteStyle::Always => ::csv::QuoteStyle::Always,
+            QuoteStyle::NonNumeric => ::csv::QuoteStyle::NonNumeric,
+            QuoteStyle::Never => ::csv::QuoteStyle::Never,
+        }
+    }
+}
+
+/// How the values of a CSV are written
+#[derive(Clone, Debug)]
+pub struct CsvWriteOptions {
+    delimiter: u8,
+    quote: u8,
+    quote_style: QuoteStyle,
+    has_headers: bool,
+    null_value: String,
+    non_finite_as_null: bool,
+    float_precision: Option<usize>,
+}
+
+impl Default for CsvWriteOptions {
+    fn default() -> CsvWriteOptions {
+        CsvWriteOptions {
+            delimiter: b',',
+            quote: b'"',
+            quote_style: QuoteStyle::Necessary,
+            has_headers: true,
+            null_value: String::new(),
+            non_finite_as_null: false,
+            float_precision: None,
+        }
+    }
+}
+
+impl CsvWriteOptions {
+    pub fn new() -> CsvWriteOptions {
+        CsvWriteOptions::default()
+    }
+
+    pub fn delimiter(mut self, delimiter: u8) -> CsvWriteOptions {
+        self.delimiter = delimiter;
+        self
+    }
+
+    pub fn quote(mut self, quote: u8) -> CsvWriteOptions {
+        self.quote = quote;
+        self
+    }
+
+    pub fn quote_style(mut self, quote_style: QuoteStyle) -> CsvWriteOptions {
+        self.quote_style = quote_style;
+        self
+    }
+
+    /// Whether the field names are written as the first row
+    pub fn has_headers(mut self, has_headers: bool) -> CsvWriteOptions {
+        self.has_headers = has_headers;
+        self
+    }
+
+    /// The value written for nulls, empty by default
+    pub fn null_value(mut self, null_value: &str) -> CsvWriteOptions {
+        self.null_value = null_value.to_string();
+        self
+    }
+
+    /// Whether NaN and infinity are written as the null value rather than `NaN`, `inf` and `-inf`
+    pub fn non_finite_as_null(mut self, non_finite_as_null: bool) -> CsvWriteOptions {
+        self.non_finite_as_null = non_finite_as_null;
+        self
+    }
+
+    /// The number of decimal places floats are written with
+    pub fn float_precision(mut self, precision: usize) -> CsvWriteOptions {
+        self.float_precision = Some(precision);
+        self
+    }
+}
+
+pub struct CsvWriter {
+    options: CsvWriteOptions,
+}
+
+impl CsvWriter {
+    pub fn with_options(options: CsvWriteOptions) -> CsvWriter {
+        CsvWriter { options }
+    }
+
+    pub fn write_path(&self, view: &View, path: &str) -> Result<(), crate::Error> {
+        let file = std::fs::File::create(path)?;
+        self.write(view, file)
+    }
+
+    pub fn write<W: Write>(&self, view: &View, writer: W) -> Result<(), crate::Error> {
+        let options = &self.options;
+
+        let mut writer = ::csv::WriterBuilder::new()
+            .delimiter(options.delimiter)
+            .quote(options.quote)
+            .quote_style(options.quote_style.as_csv())
+            .from_writer(writer);
+
+        if options.has_headers {
+            writer
+                .write_record(view.fields().iter().map(|field| field.name()))
+                .map_err(|source| Error::Write { source })?;
+        }
+
+        for row in 0..view.num_rows() {
+            let values = (0..view.num_columns())
+                .map(|col| Ok(self.format(&view.value_scalar(row, col)?)))
+                .collect::<Result<Vec<Vec<u8>>, crate::Error>>()?;
+
+            writer
+                .write_record(&values)
+                .map_err(|source| Error::Write { source })?;
+        }
+
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    fn format(&self, value: &ScalarValue) -> Vec<u8> {
+        match value {
+            ScalarValue::Null => self.options.null_value.as_bytes().to_vec(),
+            ScalarValue::String(s) => s.to_vec(),
+            ScalarValue::Binary(b) => b.to_vec(),
+            ScalarValue::Float32(v) => self.format_float(v, v.is_finite()),
+            ScalarValue::Float64(v) => self.format_float(v, v.is_finite()),
+            value => value.to_string().into_bytes(),
+        }
+    }
+
+    /// Without a precision finite floats always have a decimal place, so they are read back as
+    /// floats rather than integers
+    fn format_float<F: Display>(&self, value: F, is_finite: bool) -> Vec<u8> {
+        if !is_finite && self.options.non_finite_as_null {
+            return self.options.null_value.as_bytes().to_vec();
+        }
+
+        match self.options.float_precision {
+            Some(precision) => format!("{:.*}", precision, value).into_bytes(),
+            None => {
+                let mut value = value.to_string();
+                if is_finite && !value.contains('.') {
+                    value.push_str(".0");
+                }
+                value.into_bytes()
+            }
+        }
+    }
+}
+
+/// Replace the values of a Utf8 column which were read from a null value
+fn null_strings(
+    arr: &ArrayRef,
+    offset: usize,
+    column: usize,
+    nulls: &HashSet<(usize, usize)>,
+) -> Result<ArrayRef, crate::Error> {
+    if arr.data_type() != &DataType::Utf8 || nulls.is_empty() {
+        return Ok(arr.clone());
+    }
+
+    let arr = arr
+        .as_any()
+        .downcast_ref::<BinaryArray>()
+        .ok_or(crate::Error::InvalidDowncast)?;
+
+    let mut builder = BinaryBuilder::new(arr.len());
+
+    for i in 0..arr.len() {
+        if arr.is_null(i) || nulls.contains(&(offset + i, column)) {
+            builder.append_null()?;
+        } else {
+            builder.append_value(arr.value(i))?;
+        }
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
 #[cfg(test)]
 mod csv_test {
     use super::*;
+    use crate::query::select::Select;
+    use crate::{as_array, col, sel};
+    use arrow::array::{Float64Array, Int64Array};
+    use arrow::datatypes::Field;
+
+    fn write_temp(name: &str, contents: &str) -> String {
+        let path = std::env::temp_dir().join(name);
+        let mut file = std::fs::File::create(&path).unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    const SALES: &str = "street,city,beds,sq__ft,price\n\
+                         3526 HIGH ST,SACRAMENTO,2,836,59222\n\
+                         51 OMAHA CT,SACRAMENTO,3,1167,68212\n\
+                         2796 BRANCH ST,SACRAMENTO,2,796,68880\n";
+
+    fn assert_sales(df: &DataFrame) {
+        let view = df.view();
+
+        assert_eq!(view.num_columns(), 5);
+        assert_eq!(view.num_rows(), 3);
+
+        let beds = view.column(&col!("beds")).unwrap();
+        assert_eq!(
+            as_array!(beds, Int64Array).unwrap(),
+            &Int64Array::from(vec![2, 3, 2])
+        );
+
+        let street = view.column(&col!("street")).unwrap();
+        assert_eq!(
+            as_array!(street, BinaryArray).unwrap().value(1),
+            b"51 OMAHA CT"
+        );
+    }
 
     #[test]
     fn it_reads_from_path() {
-        let path = "/Volumes/CODE/retl/examples/csv-example/sample-csv.csv";
-        let df = CsvReader::from_path(path).unwrap();
+        let path = write_temp("alpine_csv_sales.csv", SALES);
+        let df = CsvReader::from_path(&path).unwrap();
+        assert_sales(&df);
+
+        let df = df
+            .select(vec![Select::Alias(
+                Box::new(sel!("sq__ft")),
+                "sq_ft".to_string(),
+            )])
+            .unwrap();
+        assert_eq!(df.view().field(&col!(0)).unwrap().name(), "sq_ft");
+    }
+
+    #[test]
+    fn it_reads_from_str_and_reader() {
+        assert_sales(&CsvReader::from_str(SALES).unwrap());
+        assert_sales(&CsvReader::from_reader(SALES.as_bytes()).unwrap());
+
+        let options = CsvReadOptions::new().null_values(vec!["SACRAMENTO"]);
+        let df = CsvReader::with_options(options)
+            .read(SALES.as_bytes())
+            .unwrap();
+
+        let city = df.view().column(&col!("city")).unwrap();
+        assert_eq!(city.null_count(), 3);
+    }
+
+    #[test]
+    fn it_reads_with_options() {
+        let path = write_temp(
+            "alpine_csv_read_options.csv",
+            "exported by sensor 4\n\
+             id;name;reading\n\
+             1;'north; east';1.5\n\
+             2;NA;NA\n\
+             3;south;2.5\n",
+        );
+
+        let options = CsvReadOptions::new()
+            .skip_rows(1)
+            .delimiter(b';')
+            .quote(b'\'')
+            .null_values(vec!["NA"])
+            .infer_schema_rows(None)
+            .projection(vec![2, 1]);
 
-        // dbg!(data.schema());
-        // dbg!(data.row(1));
+        let df = CsvReader::with_options(options).read_path(&path).unwrap();
+        let view = df.view();
 
-        // let col = data.column::<Int64Array, &str>("beds").unwrap();
+        assert_eq!(view.num_columns(), 2);
+        assert_eq!(view.field(&col!(0)).unwrap().name(), "reading");
+
+        let reading = view.column(&col!("reading")).unwrap();
+        assert_eq!(
+            as_array!(reading, Float64Array).unwrap(),
+            &Float64Array::from(vec![Some(1.5), None, Some(2.5)])
+        );
+
+        let name = view.column(&col!("name")).unwrap();
+        let name = as_array!(name, BinaryArray).unwrap();
+        assert_eq!(name.value(0), b"north; east");
+        assert!(name.is_null(1));
+        assert_eq!(name.value(2), b"south");
+    }
+
+    #[test]
+    fn it_reads_multiple_batches() {
+        let path = write_temp(
+            "alpine_csv_read_batches.csv",
+            "a,b\n1,x\n2,NA\n3,z\n4,w\n5,v\n",
+        );
+
+        let options = CsvReadOptions::new().batch_size(2).null_values(vec!["NA"]);
+        let df = CsvReader::with_options(options).read_path(&path).unwrap();
+        let view = df.view();
+
+        assert_eq!(view.num_columns(), 2);
+        assert_eq!(view.num_rows(), 5);
+        assert_eq!(view.chunked_column(&col!("a")).unwrap().num_chunks(), 3);
+
+        let a = view.column(&col!("a")).unwrap();
+        assert_eq!(
+            as_array!(a, Int64Array).unwrap(),
+            &Int64Array::from(vec![1, 2, 3, 4, 5])
+        );
+
+        let b = view.column(&col!("b")).unwrap();
+        let b = as_array!(b, BinaryArray).unwrap();
+        assert!(b.is_null(1));
+        assert_eq!(b.value(4), b"v");
+    }
+
+    #[test]
+    fn it_rejects_ragged_rows_whether_or_not_it_normalizes() {
+        let data = "a,b\n1,x\n2\n";
+
+        assert!(CsvReader::from_str(data).is_err());
+
+        // the normalized CSV keeps the ragged row, so it fails in arrow rather than as a rewrite
+        let options = CsvReadOptions::new().null_values(vec!["NA"]);
+        match CsvReader::with_options(options).read_str(data) {
+            Err(crate::Error::CsvError { .. }) | Ok(_) => {
+                panic!("expected arrow to reject the row")
+            }
+            Err(_) => {}
+        }
+    }
+
+    #[test]
+    fn it_writes_csv() {
+        let df = CsvReader::from_str(
+            "name,price,paid\n\
+             \"Smith, J\",10.5,true\n\
+             ,3.25,false\n\
+             Lee,,true\n",
+        )
+        .unwrap();
+
+        let mut out = vec![];
+        df.to_csv(&mut out, CsvWriteOptions::new()).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "name,price,paid\n\"Smith, J\",10.5,true\n,3.25,false\nLee,,true\n"
+        );
+
+        let options = CsvWriteOptions::new()
+            .delimiter(b';')
+            .has_headers(false)
+            .null_value("NULL")
+            .float_precision(2)
+            .quote_style(QuoteStyle::NonNumeric);
+
+        let mut out = vec![];
+        df.to_csv(&mut out, options).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\"Smith, J\";10.50;\"true\"\n\"\";3.25;\"false\"\n\"Lee\";\"NULL\";\"true\"\n"
+        );
+    }
+
+    #[test]
+    fn it_writes_floats_which_read_back_as_floats() {
+        let view = View::new(
+            vec![Field::new("reading", DataType::Float64, false)],
+            vec![Arc::new(Float64Array::from(vec![
+                1.0,
+                std::f64::NAN,
+                std::f64::NEG_INFINITY,
+                2.25,
+            ])) as ArrayRef],
+        );
+        let df = DataFrame::new(view);
+
+        let mut out = vec![];
+        df.to_csv(&mut out, CsvWriteOptions::new()).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "reading\n1.0\nNaN\n-inf\n2.25\n"
+        );
+
+        let mut out = vec![];
+        let options = CsvWriteOptions::new()
+            .null_value("NA")
+            .non_finite_as_null(true);
+        df.to_csv(&mut out, options).unwrap();
+        assert_eq!(
+            String::from_utf8(out.clone()).unwrap(),
+            "reading\n1.0\nNA\nNA\n2.25\n"
+        );
+
+        let options = CsvReadOptions::new().null_values(vec!["NA"]);
+        let read = CsvReader::with_options(options)
+            .read(out.as_slice())
+            .unwrap();
+        let reading = read.view().column(&col!("reading")).unwrap();
+        assert_eq!(
+            as_array!(reading, Float64Array).unwrap(),
+            &Float64Array::from(vec![Some(1.0), None, None, Some(2.25)])
+        );
+
+        let mut out = vec![];
+        df.to_csv(&mut out, CsvWriteOptions::new().float_precision(0))
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "reading\n1\nNaN\n-inf\n2\n"
+        );
+    }
+
+    #[test]
+    fn it_writes_to_a_path() {
+        let df = CsvReader::from_str(SALES).unwrap();
+        let path = std::env::temp_dir().join("alpine_csv_write_sales.csv");
+        let path = path.to_str().unwrap();
+
+        df.to_csv_path(path, CsvWriteOptions::new()).unwrap();
+        assert_sales(&CsvReader::from_path(path).unwrap());
+    }
+
+    #[test]
+    fn it_reads_with_a_schema() {
+        let path = write_temp("alpine_csv_read_schema.csv", "1,2\n3,4\n");
 
-        // dbg!(col.value(1));
+        let options = CsvReadOptions::new()
+            .has_headers(false)
+            .schema(Schema::new(vec![
+                Field::new("a", DataType::Int64, false),
+                Field::new("b", DataType::Utf8, false),
+            ]));
 
-        //let df = crate::dataframe::DataFrame::new(data);
-        //df.select(crate::cols!("beds", 0));
+        let df = CsvReader::with_options(options).read_path(&path).unwrap();
 
-        // let df = df.select(vec![sel!("sq__ft", "sq_ft")]).unwrap();
+        let a = df.view().column(&col!("a")).unwrap();
+        assert_eq!(
+            as_array!(a, Int64Array).unwrap(),
+            &Int64Array::from(vec![1, 3])
+        );
+        assert_eq!(
+            df.view().field(&col!("b")).unwrap().data_type(),
+            &DataType::Utf8
+        );
     }
 }
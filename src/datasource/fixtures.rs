@@ -0,0 +1,129 @@
+use crate::{as_array, Field, View};
+use arrow::array::{
+    Array, ArrayRef, BinaryBuilder, BooleanArray, Date32Array, Date64Array, Float32Array,
+    Float64Array, Int16Array, Int32Array, Int64Array, Int8Array, PrimitiveArray, Time32SecondArray,
+    Time64NanosecondArray, TimestampMicrosecondArray, UInt16Array, UInt32Array, UInt64Array,
+    UInt8Array,
+};
+use arrow::datatypes::{self as dt, ArrowNumericType, DataType, DateUnit, TimeUnit};
+use std::sync::Arc;
+
+/// A column of every data type which `value_scalar` decodes, with nulls, NaN and the extremes of
+/// the integer types
+pub fn sample() -> View {
+    let mut names = BinaryBuilder::new(3);
+    names.append_value(b"a").unwrap();
+    names.append_null().unwrap();
+    names.append_value("ünïcode".as_bytes()).unwrap();
+
+    let fields = vec![
+        Field::new("u8", DataType::UInt8, false),
+        Field::new("u16", DataType::UInt16, false),
+        Field::new("u32", DataType::UInt32, false),
+        Field::new("u64", DataType::UInt64, false),
+        Field::new("i8", DataType::Int8, true),
+        Field::new("i16", DataType::Int16, false),
+        Field::new("i32", DataType::Int32, false),
+        Field::new("i64", DataType::Int64, false),
+        Field::new("f32", DataType::Float32, false),
+        Field::new("f64", DataType::Float64, true),
+        Field::new("bool", DataType::Boolean, true),
+        Field::new("name", DataType::Utf8, true),
+    ];
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(UInt8Array::from(vec![1, 2, 3])),
+        Arc::new(UInt16Array::from(vec![1, 2, 3])),
+        Arc::new(UInt32Array::from(vec![1, 2, 3])),
+        Arc::new(UInt64Array::from(vec![1, 2, u64::max_value()])),
+        Arc::new(Int8Array::from(vec![Some(-1), None, Some(1)])),
+        Arc::new(Int16Array::from(vec![-1, 0, 1])),
+        Arc::new(Int32Array::from(vec![-1, 0, 1])),
+        Arc::new(Int64Array::from(vec![i64::min_value(), 0, 1])),
+        Arc::new(Float32Array::from(vec![0.5, 1.5, 2.5])),
+        Arc::new(Float64Array::from(vec![
+            Some(0.1),
+            None,
+            Some(std::f64::NAN),
+        ])),
+        Arc::new(BooleanArray::from(vec![Some(true), Some(false), None])),
+        Arc::new(names.finish()),
+    ];
+
+    View::new(fields, columns)
+}
+
+/// A column of each kind of date, time and timestamp, compared with `temporal_values`
+pub fn temporal() -> View {
+    let fields = vec![
+        Field::new("date32", DataType::Date32(DateUnit::Day), true),
+        Field::new("date64", DataType::Date64(DateUnit::Millisecond), false),
+        Field::new("time32", DataType::Time32(TimeUnit::Second), false),
+        Field::new("time64", DataType::Time64(TimeUnit::Nanosecond), false),
+        Field::new(
+            "timestamp",
+            DataType::Timestamp(TimeUnit::Microsecond),
+            true,
+        ),
+    ];
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(Date32Array::from(vec![Some(-1), None, Some(18_000)])),
+        Arc::new(Date64Array::from(vec![-86_400_000, 0, 1_555_000_000_000])),
+        Arc::new(Time32SecondArray::from(vec![0, 3_600, 86_399])),
+        Arc::new(Time64NanosecondArray::from(vec![0, 1, 86_399_999_999_999])),
+        Arc::new(TimestampMicrosecondArray::from(vec![
+            Some(i64::min_value()),
+            None,
+            Some(1_555_000_000_000_000),
+        ])),
+    ];
+
+    View::new(fields, columns)
+}
+
+/// The rows of the view formatted for comparing two views
+pub fn rows(view: &View) -> Vec<String> {
+    (0..view.num_rows())
+        .map(|i| format!("{:?}", view.row(i).unwrap()))
+        .collect()
+}
+
+/// The values of the temporal columns, which `rows` can't format as `value_scalar` doesn't decode
+/// them
+pub fn temporal_values(view: &View) -> Vec<Vec<Option<i64>>> {
+    (0..view.num_columns())
+        .map(|i| {
+            let arr = view.column(&i.into()).unwrap();
+
+            match arr.data_type() {
+                DataType::Date32(_) => values::<dt::Date32Type>(&arr),
+                DataType::Date64(_) => values::<dt::Date64Type>(&arr),
+                DataType::Time32(TimeUnit::Second) => values::<dt::Time32SecondType>(&arr),
+                DataType::Time64(TimeUnit::Nanosecond) => values::<dt::Time64NanosecondType>(&arr),
+                DataType::Timestamp(TimeUnit::Microsecond) => {
+                    values::<dt::TimestampMicrosecondType>(&arr)
+                }
+                data_type => panic!("{:?} isn't in the temporal fixture", data_type),
+            }
+        })
+        .collect()
+}
+
+fn values<T>(arr: &ArrayRef) -> Vec<Option<i64>>
+where
+    T: ArrowNumericType,
+    T::Native: Into<i64>,
+{
+    let arr = as_array!(arr, PrimitiveArray<T>).unwrap();
+
+    (0..arr.len())
+        .map(|i| {
+            if arr.is_null(i) {
+                None
+            } else {
+                Some(arr.value(i).into())
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,208 @@
+use crate::{ChunkedArray, DataFrame, View};
+use arrow::array::ArrayRef;
+use arrow::datatypes::{Schema, SchemaRef};
+use arrow::ipc::reader::{FileReader, StreamReader};
+use arrow::ipc::writer::{FileWriter, StreamWriter};
+use arrow::record_batch::RecordBatch;
+use std::io::{BufReader, BufWriter, Read, Seek, Write};
+use std::sync::Arc;
+
+/// The arrow IPC formats, the file format can be read in any order while the stream format is
+/// read from start to end
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IpcFormat {
+    File,
+    Stream,
+}
+
+pub struct IpcReader {}
+
+impl IpcReader {
+    /// Read a file in the IPC file format
+    pub fn from_path(path: &str) -> Result<DataFrame, crate::Error> {
+        let file = std::fs::File::open(path)?;
+        IpcReader::read_file(BufReader::new(file))
+    }
+
+    pub fn read_file<R: Read + Seek>(reader: R) -> Result<DataFrame, crate::Error> {
+        let mut reader = FileReader::try_new(reader)?;
+        let schema = reader.schema();
+        to_df(schema, || reader.next())
+    }
+
+    pub fn read_stream<R: Read>(reader: R) -> Result<DataFrame, crate::Error> {
+        let mut reader = StreamReader::try_new(reader)?;
+        let schema = reader.schema();
+        to_df(schema, || reader.next())
+    }
+}
+
+pub struct IpcWriter {}
+
+impl IpcWriter {
+    pub fn write_path(view: &View, path: &str, format: IpcFormat) -> Result<(), crate::Error> {
+        let file = std::fs::File::create(path)?;
+        IpcWriter::write(view, BufWriter::new(file), format)
+    }
+
+    pub fn write<W: Write>(view: &View, writer: W, format: IpcFormat) -> Result<(), crate::Error> {
+        let schema = Schema::new(view.fields().clone());
+        let batches = batches(view)?;
+
+        match format {
+            IpcFormat::File => {
+                let mut writer = FileWriter::try_new(writer, &schema)?;
+                for batch in batches.iter() {
+                    writer.write(batch)?;
+                }
+                writer.finish()?;
+            }
+            IpcFormat::Stream => {
+                let mut writer = StreamWriter::try_new(writer, &schema)?;
+                for batch in batches.iter() {
+                    writer.write(batch)?;
+                }
+                writer.finish()?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Collect the record batches into a data frame, each batch becomes a chunk of the columns
+fn to_df<F>(schema: SchemaRef, mut next: F) -> Result<DataFrame, crate::Error>
+where
+    F: FnMut() -> arrow::error::Result<Option<RecordBatch>>,
+{
+    let fields = schema.fields().to_owned();
+    let mut chunks: Vec<Vec<ArrayRef>> = vec![vec![]; fields.len()];
+
+    while let Some(batch) = next()? {
+        for idx in 0..batch.num_columns() {
+            chunks[idx].push(batch.column(idx).to_owned());
+        }
+    }
+
+    let columns = fields
+        .iter()
+        .zip(chunks.into_iter())
+        .map(|(field, chunks)| ChunkedArray::new(field.data_type().clone(), chunks))
+        .collect::<Result<Vec<_>, crate::Error>>()?;
+
+    Ok(DataFrame::new(View::from_chunks(fields, columns)))
+}
+
+/// Split the view into record batches. When every column is chunked at the same rows the chunks
+/// are written as they are, otherwise the columns are concatenated into a single batch.
+fn batches(view: &View) -> Result<Vec<RecordBatch>, crate::Error> {
+    let schema = Arc::new(Schema::new(view.fields().clone()));
+    let columns = view.columns();
+
+    if columns.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let lengths = |arr: &ChunkedArray| -> Vec<usize> {
+        arr.chunks().iter().map(|chunk| chunk.len()).collect()
+    };
+    let first = lengths(&columns[0]);
+
+    if columns.iter().all(|arr| lengths(arr) == first) {
+        return (0..first.len())
+            .map(|i| {
+                let arrays = columns.iter().map(|arr| arr.chunks()[i].clone()).collect();
+                RecordBatch::try_new(schema.clone(), arrays).map_err(crate::Error::from)
+            })
+            .collect();
+    }
+
+    let arrays = columns
+        .iter()
+        .map(|arr| arr.to_array())
+        .collect::<Result<Vec<_>, crate::Error>>()?;
+
+    Ok(vec![RecordBatch::try_new(schema, arrays)?])
+}
+
+#[cfg(test)]
+mod test_ipc {
+    use super::*;
+    use crate::col;
+    use crate::datasource::fixtures::{rows, sample, temporal, temporal_values};
+    use crate::datatype::ScalarValue;
+    use std::io::Cursor;
+
+    #[test]
+    fn it_round_trips_the_file_and_stream_formats() {
+        let view = sample();
+
+        for format in [IpcFormat::File, IpcFormat::Stream].iter() {
+            let mut data = vec![];
+            IpcWriter::write(&view, &mut data, *format).unwrap();
+
+            let df = match *format {
+                IpcFormat::File => IpcReader::read_file(Cursor::new(data)).unwrap(),
+                IpcFormat::Stream => IpcReader::read_stream(data.as_slice()).unwrap(),
+            };
+
+            assert_eq!(df.view().fields(), view.fields());
+            assert_eq!(rows(df.view()), rows(&view));
+        }
+    }
+
+    #[test]
+    fn it_round_trips_temporal_columns() {
+        let view = temporal();
+
+        for format in [IpcFormat::File, IpcFormat::Stream].iter() {
+            let mut data = vec![];
+            IpcWriter::write(&view, &mut data, *format).unwrap();
+
+            let df = match *format {
+                IpcFormat::File => IpcReader::read_file(Cursor::new(data)).unwrap(),
+                IpcFormat::Stream => IpcReader::read_stream(data.as_slice()).unwrap(),
+            };
+
+            assert_eq!(df.view().fields(), view.fields());
+            assert_eq!(temporal_values(df.view()), temporal_values(&view));
+        }
+    }
+
+    #[test]
+    fn it_keeps_the_chunks() {
+        let mut view = sample();
+        view.append(&sample()).unwrap();
+
+        let path = std::env::temp_dir().join("alpine_ipc_chunks.arrow");
+        let path = path.to_str().unwrap();
+
+        IpcWriter::write_path(&view, path, IpcFormat::File).unwrap();
+        let df = IpcReader::from_path(path).unwrap();
+
+        assert_eq!(df.view().num_rows(), 6);
+        assert_eq!(
+            df.view().chunked_column(&col!("i8")).unwrap().num_chunks(),
+            2
+        );
+        assert_eq!(
+            df.view().value_scalar(5, 11).unwrap().as_string().unwrap(),
+            "ünïcode"
+        );
+        assert_eq!(df.view().value_scalar(4, 4).unwrap(), ScalarValue::Null);
+    }
+
+    #[test]
+    fn it_writes_the_stream_format_to_a_path() {
+        let path = std::env::temp_dir().join("alpine_ipc_stream.arrows");
+        let path = path.to_str().unwrap();
+
+        DataFrame::new(sample())
+            .to_ipc_path(path, IpcFormat::Stream)
+            .unwrap();
+
+        let file = std::fs::File::open(path).unwrap();
+        let df = IpcReader::read_stream(BufReader::new(file)).unwrap();
+        assert_eq!(rows(df.view()), rows(&sample()));
+    }
+}
@@ -0,0 +1,500 @@
+use crate::datatype::ScalarValue;
+use crate::{ChunkedArray, DataFrame, View};
+use arrow::array::ArrayRef;
+use arrow::datatypes::{Schema, SchemaRef};
+use bstr::ByteSlice;
+use serde_json::{Map, Value};
+use snafu::Snafu;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, BufWriter, Cursor, Read, Write};
+use std::sync::Arc;
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("Failed to parse the JSON on line {}: {}", line, source))]
+    Parse {
+        line: usize,
+        source: serde_json::Error,
+    },
+
+    #[snafu(display("Expected a JSON object on line {}", line))]
+    NotAnObject { line: usize },
+
+    #[snafu(display("Failed to write the flattened JSON: {}", source))]
+    Flatten { source: serde_json::Error },
+
+    #[snafu(display("Failed to write the JSON: {}", source))]
+    Write { source: serde_json::Error },
+}
+
+/// How the objects of newline-delimited JSON are turned into columns
+#[derive(Clone, Debug)]
+pub struct JsonReadOptions {
+    schema: Option<SchemaRef>,
+    infer_schema_rows: Option<usize>,
+    batch_size: usize,
+    flatten: bool,
+}
+
+impl Default for JsonReadOptions {
+    fn default() -> JsonReadOptions {
+        JsonReadOptions {
+            schema: None,
+            infer_schema_rows: Some(20),
+            batch_size: 1024,
+            flatten: true,
+        }
+    }
+}
+
+impl JsonReadOptions {
+    pub fn new() -> JsonReadOptions {
+        JsonReadOptions::default()
+    }
+
+    /// Use the schema instead of inferring one from the data, nested fields are named by their
+    /// flattened path e.g. `address.city`
+    pub fn schema(mut self, schema: Schema) -> JsonReadOptions {
+        self.schema = Some(Arc::new(schema));
+        self
+    }
+
+    /// Lines scanned for the schema, `None` scans them all
+    pub fn infer_schema_rows(mut self, rows: Option<usize>) -> JsonReadOptions {
+        self.infer_schema_rows = rows;
+        self
+    }
+
+    /// The number of rows parsed at a time
+    pub fn batch_size(mut self, batch_size: usize) -> JsonReadOptions {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Whether nested objects are flattened into columns with dotted names, e.g.
+    /// `{"address": {"city": "Boston"}}` becomes the column `address.city`
+    pub fn flatten(mut self, flatten: bool) -> JsonReadOptions {
+        self.flatten = flatten;
+        self
+    }
+}
+
+pub struct JsonReader {
+    options: JsonReadOptions,
+}
+
+impl JsonReader {
+    pub fn from_path(path: &str) -> Result<DataFrame, crate::Error> {
+        JsonReader::with_options(JsonReadOptions::default()).read_path(path)
+    }
+
+    pub fn from_reader<R: Read>(reader: R) -> Result<DataFrame, crate::Error> {
+        JsonReader::with_options(JsonReadOptions::default()).read(reader)
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(data: &str) -> Result<DataFrame, crate::Error> {
+        JsonReader::with_options(JsonReadOptions::default()).read_str(data)
+    }
+
+    pub fn with_options(options: JsonReadOptions) -> JsonReader {
+        JsonReader { options }
+    }
+
+    pub fn read_path(&self, path: &str) -> Result<DataFrame, crate::Error> {
+        let file = std::fs::File::open(path)?;
+        self.read(file)
+    }
+
+    pub fn read_str(&self, data: &str) -> Result<DataFrame, crate::Error> {
+        self.read(data.as_bytes())
+    }
+
+    /// Read one JSON object per line. The data is buffered in memory since the schema inference
+    /// must seek back to the start.
+    pub fn read<R: Read>(&self, reader: R) -> Result<DataFrame, crate::Error> {
+        let options = &self.options;
+        let (data, keys) = normalize(reader, options.flatten)?;
+
+        let mut builder = arrow::json::ReaderBuilder::new().with_batch_size(options.batch_size);
+
+        builder = match &options.schema {
+            Some(schema) => builder.with_schema(schema.clone()),
+            None => builder.infer_schema(options.infer_schema_rows),
+        };
+
+        let mut reader = builder.build(Cursor::new(data))?;
+
+        let schema = reader.schema();
+        let fields = schema.fields().to_owned();
+
+        let mut chunks: Vec<Vec<ArrayRef>> = vec![vec![]; fields.len()];
+
+        while let Some(batch) = reader.next()? {
+            for idx in 0..batch.num_columns() {
+                chunks[idx].push(batch.column(idx).to_owned());
+            }
+        }
+
+        let mut columns = fields
+            .into_iter()
+            .zip(chunks.into_iter())
+            .map(|(field, chunks)| {
+                let arr = ChunkedArray::new(field.data_type().clone(), chunks)?;
+                Ok((field, arr))
+            })
+            .collect::<Result<Vec<_>, crate::Error>>()?;
+
+        // the inferred schema doesn't keep the order of the keys, so the columns are put back into
+        // the order the keys were first seen in
+        if options.schema.is_none() {
+            columns.sort_by_key(|(field, _)| keys.get(field.name()).copied());
+        }
+
+        let (fields, columns) = columns.into_iter().unzip();
+
+        Ok(DataFrame::new(View::from_chunks(fields, columns)))
+    }
+}
+
+/// The layout of the written rows
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JsonFormat {
+    /// A single array holding an object per row
+    Records,
+    /// An object per line, also known as NDJSON
+    Lines,
+}
+
+/// How the rows are written as JSON
+#[derive(Clone, Debug)]
+pub struct JsonWriteOptions {
+    format: JsonFormat,
+    skip_nulls: bool,
+    float_precision: Option<usize>,
+}
+
+impl Default for JsonWriteOptions {
+    fn default() -> JsonWriteOptions {
+        JsonWriteOptions {
+            format: JsonFormat::Lines,
+            skip_nulls: false,
+            float_precision: None,
+        }
+    }
+}
+
+impl JsonWriteOptions {
+    pub fn new() -> JsonWriteOptions {
+        JsonWriteOptions::default()
+    }
+
+    pub fn format(mut self, format: JsonFormat) -> JsonWriteOptions {
+        self.format = format;
+        self
+    }
+
+    /// Leave the keys of null values out of the objects instead of writing `null`
+    pub fn skip_nulls(mut self, skip_nulls: bool) -> JsonWriteOptions {
+        self.skip_nulls = skip_nulls;
+        self
+    }
+
+    /// Fixed decimal places for floats, otherwise the shortest exact form
+    pub fn float_precision(mut self, precision: usize) -> JsonWriteOptions {
+        self.float_precision = Some(precision);
+        self
+    }
+}
+
+pub struct JsonWriter {
+    options: JsonWriteOptions,
+}
+
+impl JsonWriter {
+    pub fn with_options(options: JsonWriteOptions) -> JsonWriter {
+        JsonWriter { options }
+    }
+
+    pub fn write_path(&self, view: &View, path: &str) -> Result<(), crate::Error> {
+        let file = std::fs::File::create(path)?;
+        self.write(view, BufWriter::new(file))
+    }
+
+    /// Write the rows one at a time to the writer, the rows aren't collected in memory first
+    pub fn write<W: Write>(&self, view: &View, mut writer: W) -> Result<(), crate::Error> {
+        if self.options.format == JsonFormat::Records {
+            writer.write_all(b"[")?;
+        }
+
+        for row in 0..view.num_rows() {
+            if self.options.format == JsonFormat::Records && row > 0 {
+                writer.write_all(b",")?;
+            }
+
+            self.write_row(view, row, &mut writer)?;
+
+            if self.options.format == JsonFormat::Lines {
+                writer.write_all(b"\n")?;
+            }
+        }
+
+        if self.options.format == JsonFormat::Records {
+            writer.write_all(b"]")?;
+        }
+
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    fn write_row<W: Write>(
+        &self,
+        view: &View,
+        row: usize,
+        writer: &mut W,
+    ) -> Result<(), crate::Error> {
+        writer.write_all(b"{")?;
+
+        let mut first = true;
+
+        for (col, field) in view.fields().iter().enumerate() {
+            let value = view.value_scalar(row, col)?;
+
+            if value.is_null() && self.options.skip_nulls {
+                continue;
+            }
+
+            if !first {
+                writer.write_all(b",")?;
+            }
+            first = false;
+
+            serde_json::to_writer(&mut *writer, field.name())
+                .map_err(|source| Error::Write { source })?;
+            writer.write_all(b":")?;
+            self.write_value(&value, writer)?;
+        }
+
+        writer.write_all(b"}")?;
+
+        Ok(())
+    }
+
+    fn write_value<W: Write>(
+        &self,
+        value: &ScalarValue,
+        writer: &mut W,
+    ) -> Result<(), crate::Error> {
+        let precision = self.options.float_precision;
+
+        match value {
+            ScalarValue::Null => writer.write_all(b"null")?,
+            // JSON numbers can't be NaN or infinite
+            ScalarValue::Float32(v) if !v.is_finite() => writer.write_all(b"null")?,
+            ScalarValue::Float64(v) if !v.is_finite() => writer.write_all(b"null")?,
+            ScalarValue::Float32(v) => match precision {
+                Some(precision) => write!(writer, "{:.*}", precision, v)?,
+                None => serde_json::to_writer(&mut *writer, v)
+                    .map_err(|source| Error::Write { source })?,
+            },
+            ScalarValue::Float64(v) => match precision {
+                Some(precision) => write!(writer, "{:.*}", precision, v)?,
+                None => serde_json::to_writer(&mut *writer, v)
+                    .map_err(|source| Error::Write { source })?,
+            },
+            // strings are escaped by serde, invalid UTF-8 is replaced
+            ScalarValue::String(s) => serde_json::to_writer(&mut *writer, &s.to_str_lossy())
+                .map_err(|source| Error::Write { source })?,
+            ScalarValue::Binary(b) => {
+                serde_json::to_writer(&mut *writer, &String::from_utf8_lossy(b))
+                    .map_err(|source| Error::Write { source })?
+            }
+            value => write!(writer, "{}", value)?,
+        }
+
+        Ok(())
+    }
+}
+
+/// Check each line is an object and rewrite it, optionally with the nested objects replaced by
+/// their dotted paths. Blank lines are skipped. The keys are returned with the position they were
+/// first seen at.
+fn normalize<R: Read>(
+    reader: R,
+    flatten: bool,
+) -> Result<(Vec<u8>, HashMap<String, usize>), crate::Error> {
+    let mut data = vec![];
+    let mut keys = HashMap::new();
+
+    for (i, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line?;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let value: Value = serde_json::from_str(&line).map_err(|source| Error::Parse {
+            line: i + 1,
+            source,
+        })?;
+
+        let object = match value {
+            Value::Object(object) => object,
+            _ => return Err(Error::NotAnObject { line: i + 1 }.into()),
+        };
+
+        let object = if flatten {
+            let mut flat = Map::with_capacity(object.len());
+            flatten_object(None, object, &mut flat);
+            flat
+        } else {
+            object
+        };
+
+        for key in object.keys() {
+            if !keys.contains_key(key) {
+                keys.insert(key.clone(), keys.len());
+            }
+        }
+
+        serde_json::to_writer(&mut data, &Value::Object(object))
+            .map_err(|source| Error::Flatten { source })?;
+        data.push(b'\n');
+    }
+
+    Ok((data, keys))
+}
+
+fn flatten_object(prefix: Option<&str>, object: Map<String, Value>, flat: &mut Map<String, Value>) {
+    for (key, value) in object {
+        let key = match prefix {
+            Some(prefix) => format!("{}.{}", prefix, key),
+            None => key,
+        };
+
+        match value {
+            Value::Object(object) => flatten_object(Some(&key), object, flat),
+            value => {
+                flat.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_json {
+    use super::*;
+    use crate::{as_array, col};
+    use arrow::array::{BinaryArray, Float64Array, Int64Array};
+    use arrow::datatypes::{DataType, Field};
+
+    const PEOPLE: &str = r#"{"name": "Ada", "age": 36, "address": {"city": "London", "geo": {"lat": 51.5}}}
+{"name": "Grace", "age": 85, "address": {"city": "New York"}}
+
+{"name": "Alan", "address": {"city": "Wilmslow", "geo": {"lat": 53.3}}}
+"#;
+
+    #[test]
+    fn it_reads_and_flattens_json() {
+        let df = JsonReader::from_str(PEOPLE).unwrap();
+        let view = df.view();
+
+        assert_eq!(view.num_rows(), 3);
+        assert_eq!(view.num_columns(), 4);
+
+        let names: Vec<&String> = view.fields().iter().map(|field| field.name()).collect();
+        assert_eq!(
+            names,
+            vec!["name", "age", "address.city", "address.geo.lat"]
+        );
+
+        let age = view.column(&col!("age")).unwrap();
+        assert_eq!(
+            as_array!(age, Int64Array).unwrap(),
+            &Int64Array::from(vec![Some(36), Some(85), None])
+        );
+
+        let city = view.column(&col!("address.city")).unwrap();
+        assert_eq!(as_array!(city, BinaryArray).unwrap().value(2), b"Wilmslow");
+
+        let lat = view.column(&col!("address.geo.lat")).unwrap();
+        assert_eq!(
+            as_array!(lat, Float64Array).unwrap(),
+            &Float64Array::from(vec![Some(51.5), None, Some(53.3)])
+        );
+    }
+
+    #[test]
+    fn it_reads_json_with_a_schema() {
+        let options = JsonReadOptions::new().schema(Schema::new(vec![
+            Field::new("name", DataType::Utf8, false),
+            Field::new("address.geo.lat", DataType::Float64, true),
+        ]));
+
+        let df = JsonReader::with_options(options)
+            .read(PEOPLE.as_bytes())
+            .unwrap();
+
+        assert_eq!(df.view().num_columns(), 2);
+        assert_eq!(df.view().field(&col!(1)).unwrap().name(), "address.geo.lat");
+    }
+
+    #[test]
+    fn it_writes_json() {
+        let df = JsonReader::from_str(
+            r#"{"name": "Ada \"Countess\" Lovelace", "score": 1.5, "active": true}
+{"name": "Grace\nHopper", "score": null, "active": false}
+"#,
+        )
+        .unwrap();
+
+        let mut out = vec![];
+        df.to_json(&mut out, JsonWriteOptions::new()).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            r#"{"name":"Ada \"Countess\" Lovelace","score":1.5,"active":true}
+{"name":"Grace\nHopper","score":null,"active":false}
+"#
+        );
+
+        let options = JsonWriteOptions::new()
+            .format(JsonFormat::Records)
+            .skip_nulls(true)
+            .float_precision(2);
+
+        let mut out = vec![];
+        df.to_json(&mut out, options).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            r#"[{"name":"Ada \"Countess\" Lovelace","score":1.50,"active":true},{"name":"Grace\nHopper","active":false}]"#
+        );
+    }
+
+    #[test]
+    fn it_writes_json_which_reads_back() {
+        let df = JsonReader::from_str(PEOPLE).unwrap();
+
+        let mut out = vec![];
+        df.to_json(&mut out, JsonWriteOptions::new().skip_nulls(true))
+            .unwrap();
+
+        let read = JsonReader::from_reader(out.as_slice()).unwrap();
+        assert_eq!(read.view().num_rows(), 3);
+
+        let lat = read.view().column(&col!("address.geo.lat")).unwrap();
+        assert_eq!(
+            as_array!(lat, Float64Array).unwrap(),
+            &Float64Array::from(vec![Some(51.5), None, Some(53.3)])
+        );
+    }
+
+    #[test]
+    fn it_rejects_lines_which_are_not_objects() {
+        let res = JsonReader::from_str("{\"a\": 1}\n[1, 2]\n");
+        assert!(res.is_err());
+
+        let res = JsonReader::from_str("{\"a\": 1}\n{\"a\": \n");
+        assert!(res.is_err());
+    }
+}
@@ -1 +1,7 @@
 pub mod csv;
+#[cfg(test)]
+pub(crate) mod fixtures;
+pub mod ipc;
+pub mod json;
+#[cfg(feature = "parquet")]
+pub mod parquet;
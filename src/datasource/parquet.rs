@@ -0,0 +1,971 @@
+use crate::column::Column;
+use crate::datatype::{is_lossy_promotion, promote, ScalarValue};
+use crate::ops::cmp::{is_nan, total_cmp, CmpOp};
+use crate::query::filter::{filter_with_options, Filter};
+use crate::query::QueryOptions;
+use crate::{as_array, ChunkedArray, DataFrame, Field, View};
+use ::parquet::basic::{LogicalType, Repetition, Type as PhysicalType};
+use ::parquet::column::reader::{ColumnReader, ColumnReaderImpl};
+use ::parquet::column::writer::{ColumnWriter, ColumnWriterImpl};
+use ::parquet::data_type::{ByteArray, DataType as ParquetType};
+use ::parquet::errors::ParquetError;
+use ::parquet::file::metadata::ColumnChunkMetaData;
+use ::parquet::file::properties::WriterProperties;
+use ::parquet::file::reader::{FileReader, SerializedFileReader};
+use ::parquet::file::statistics::Statistics;
+use ::parquet::file::writer::{FileWriter, RowGroupWriter, SerializedFileWriter};
+use ::parquet::schema::types::{ColumnDescriptor, Type, TypePtr};
+use arrow::array::{Array, ArrayRef, BinaryArray, BinaryBuilder, PrimitiveArray, PrimitiveBuilder};
+use arrow::datatypes::{self as dt, ArrowPrimitiveType, DataType};
+use std::cmp::Ordering;
+use std::ops::Range;
+use std::rc::Rc;
+use std::sync::Arc;
+
+impl From<ParquetError> for crate::Error {
+    fn from(error: ParquetError) -> crate::Error {
+        crate::Error::Parquet {
+            inner: error.to_string(),
+        }
+    }
+}
+
+/// Which columns, row groups and rows of a Parquet file are read
+#[derive(Clone, Debug, Default)]
+pub struct ParquetReadOptions<'a> {
+    projection: Option<Vec<usize>>,
+    row_groups: Option<Vec<usize>>,
+    filter: Option<Filter<'a>>,
+    query_options: QueryOptions,
+}
+
+impl<'a> ParquetReadOptions<'a> {
+    pub fn new() -> ParquetReadOptions<'a> {
+        ParquetReadOptions::default()
+    }
+
+    /// Only read the columns at the indexes, in the given order
+    pub fn projection(mut self, projection: Vec<usize>) -> ParquetReadOptions<'a> {
+        self.projection = Some(projection);
+        self
+    }
+
+    /// Only read the row groups at the indexes
+    pub fn row_groups(mut self, row_groups: Vec<usize>) -> ParquetReadOptions<'a> {
+        self.row_groups = Some(row_groups);
+        self
+    }
+
+    /// Filter the rows, row groups whose statistics show that no row can match are not read. The
+    /// columns of the filter refer to the columns after the projection.
+    pub fn filter(mut self, filter: Filter<'a>) -> ParquetReadOptions<'a> {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// The options the filter is evaluated with, such as the ordering of NaN
+    pub fn query_options(mut self, query_options: QueryOptions) -> ParquetReadOptions<'a> {
+        self.query_options = query_options;
+        self
+    }
+}
+
+pub struct ParquetReader<'a> {
+    options: ParquetReadOptions<'a>,
+}
+
+impl<'a> ParquetReader<'a> {
+    pub fn from_path(path: &str) -> Result<DataFrame, crate::Error> {
+        ParquetReader::with_options(ParquetReadOptions::default()).read_path(path)
+    }
+
+    pub fn with_options(options: ParquetReadOptions<'a>) -> ParquetReader<'a> {
+        ParquetReader { options }
+    }
+
+    pub fn read_path(&self, path: &str) -> Result<DataFrame, crate::Error> {
+        let file = std::fs::File::open(path)?;
+        let reader = SerializedFileReader::new(file)?;
+
+        let metadata = reader.metadata();
+        let schema = metadata.file_metadata().schema_descr();
+
+        let indices = match &self.options.projection {
+            Some(projection) => projection.clone(),
+            None => (0..schema.num_columns()).collect(),
+        };
+        check_bounds(&indices, schema.num_columns())?;
+
+        let fields = indices
+            .iter()
+            .map(|i| arrow_field(&schema.column(*i)))
+            .collect::<Result<Vec<Field>, crate::Error>>()?;
+
+        let row_groups = match &self.options.row_groups {
+            Some(row_groups) => row_groups.clone(),
+            None => (0..metadata.num_row_groups()).collect(),
+        };
+        check_bounds(&row_groups, metadata.num_row_groups())?;
+
+        let mut chunks: Vec<Vec<ArrayRef>> = vec![vec![]; fields.len()];
+
+        for row_group in row_groups {
+            let row_group_metadata = metadata.row_group(row_group);
+            let num_rows = row_group_metadata.num_rows() as usize;
+
+            if let Some(filter) = &self.options.filter {
+                let stats: Vec<(&str, Option<ColumnStats>)> = indices
+                    .iter()
+                    .zip(fields.iter())
+                    .map(|(i, field)| {
+                        let column = row_group_metadata.column(*i);
+                        (
+                            field.name().as_str(),
+                            column_stats(column, field.data_type()),
+                        )
+                    })
+                    .collect();
+
+                if !may_match(filter, &stats, num_rows as u64) {
+                    continue;
+                }
+            }
+
+            let row_group_reader = reader.get_row_group(row_group)?;
+
+            for (j, i) in indices.iter().enumerate() {
+                let max_def_level = schema.column(*i).max_def_level();
+                let column_reader = row_group_reader.get_column_reader(*i)?;
+
+                chunks[j].push(read_column(
+                    column_reader,
+                    fields[j].data_type(),
+                    max_def_level,
+                    num_rows,
+                )?);
+            }
+        }
+
+        let columns = fields
+            .iter()
+            .zip(chunks.into_iter())
+            .map(|(field, chunks)| ChunkedArray::new(field.data_type().clone(), chunks))
+            .collect::<Result<Vec<_>, crate::Error>>()?;
+
+        let view = View::from_chunks(fields, columns);
+
+        // the statistics only rule out whole row groups, the rows of the groups which were read
+        // still need to be filtered
+        let view = match &self.options.filter {
+            Some(filter) => {
+                filter_with_options(&view, filter.clone(), &self.options.query_options)?
+            }
+            None => view,
+        };
+
+        Ok(DataFrame::new(view))
+    }
+}
+
+/// How a Parquet file is laid out when written
+#[derive(Clone, Debug)]
+pub struct ParquetWriteOptions {
+    row_group_size: usize,
+}
+
+impl Default for ParquetWriteOptions {
+    fn default() -> ParquetWriteOptions {
+        ParquetWriteOptions {
+            row_group_size: 64 * 1024,
+        }
+    }
+}
+
+impl ParquetWriteOptions {
+    pub fn new() -> ParquetWriteOptions {
+        ParquetWriteOptions::default()
+    }
+
+    /// The maximum number of rows in each row group
+    pub fn row_group_size(mut self, row_group_size: usize) -> ParquetWriteOptions {
+        self.row_group_size = row_group_size.max(1);
+        self
+    }
+}
+
+pub struct ParquetWriter {
+    options: ParquetWriteOptions,
+}
+
+impl ParquetWriter {
+    pub fn with_options(options: ParquetWriteOptions) -> ParquetWriter {
+        ParquetWriter { options }
+    }
+
+    pub fn write_path(&self, view: &View, path: &str) -> Result<(), crate::Error> {
+        let arrays = view
+            .columns()
+            .iter()
+            .map(|arr| arr.to_array())
+            .collect::<Result<Vec<_>, crate::Error>>()?;
+
+        // columns which hold nulls are written as optional even when the field isn't nullable
+        let optional: Vec<bool> = view
+            .fields()
+            .iter()
+            .zip(arrays.iter())
+            .map(|(field, arr)| field.is_nullable() || arr.null_count() > 0)
+            .collect();
+
+        let mut fields = view
+            .fields()
+            .iter()
+            .zip(optional.iter())
+            .map(|(field, optional)| parquet_type(field, *optional))
+            .collect::<Result<Vec<TypePtr>, crate::Error>>()?;
+
+        let schema = Type::group_type_builder("schema")
+            .with_fields(&mut fields)
+            .build()?;
+        let properties = WriterProperties::builder().build();
+
+        let file = std::fs::File::create(path)?;
+        let mut writer = SerializedFileWriter::new(file, Rc::new(schema), Rc::new(properties))?;
+
+        let num_rows = view.num_rows();
+        let mut start = 0;
+
+        while start < num_rows {
+            let end = (start + self.options.row_group_size).min(num_rows);
+            let mut row_group_writer = writer.next_row_group()?;
+
+            for (arr, optional) in arrays.iter().zip(optional.iter()) {
+                let mut column_writer = match row_group_writer.next_column()? {
+                    Some(column_writer) => column_writer,
+                    None => break,
+                };
+
+                write_column(&mut column_writer, arr, start..end, *optional)?;
+                row_group_writer.close_column(column_writer)?;
+            }
+
+            writer.close_row_group(row_group_writer)?;
+            start = end;
+        }
+
+        writer.close()?;
+
+        Ok(())
+    }
+}
+
+fn check_bounds(indices: &[usize], len: usize) -> Result<(), crate::Error> {
+    match indices.iter().find(|i| **i >= len) {
+        Some(index) => Err(crate::Error::IndexOutOfBounds { index: *index, len }),
+        None => Ok(()),
+    }
+}
+
+/// The field the parquet column is read into, nested columns are named by their dotted path since
+/// the leaf names may clash
+fn arrow_field(descr: &ColumnDescriptor) -> Result<Field, crate::Error> {
+    Ok(Field::new(
+        &descr.path().string(),
+        arrow_type(descr)?,
+        descr.max_def_level() > 0,
+    ))
+}
+
+/// The arrow data type the values of the parquet column are read into, repeated columns such as
+/// lists can't be read into a flat column
+fn arrow_type(descr: &ColumnDescriptor) -> Result<DataType, crate::Error> {
+    if descr.max_rep_level() > 0 {
+        return Err(crate::Error::Parquet {
+            inner: format!(
+                "The column {} is repeated, which is not supported",
+                descr.path().string()
+            ),
+        });
+    }
+
+    let data_type = match (descr.physical_type(), descr.logical_type()) {
+        (PhysicalType::BOOLEAN, _) => DataType::Boolean,
+        (PhysicalType::INT32, LogicalType::INT_8) => DataType::Int8,
+        (PhysicalType::INT32, LogicalType::INT_16) => DataType::Int16,
+        (PhysicalType::INT32, LogicalType::UINT_8) => DataType::UInt8,
+        (PhysicalType::INT32, LogicalType::UINT_16) => DataType::UInt16,
+        (PhysicalType::INT32, LogicalType::UINT_32) => DataType::UInt32,
+        (PhysicalType::INT32, _) => DataType::Int32,
+        (PhysicalType::INT64, LogicalType::UINT_64) => DataType::UInt64,
+        (PhysicalType::INT64, _) => DataType::Int64,
+        (PhysicalType::FLOAT, _) => DataType::Float32,
+        (PhysicalType::DOUBLE, _) => DataType::Float64,
+        (PhysicalType::BYTE_ARRAY, _) => DataType::Utf8,
+        (physical_type, _) => {
+            return Err(crate::Error::Parquet {
+                inner: format!(
+                    "The column {} has the unsupported type {:?}",
+                    descr.path().string(),
+                    physical_type
+                ),
+            })
+        }
+    };
+
+    Ok(data_type)
+}
+
+fn parquet_type(field: &Field, optional: bool) -> Result<TypePtr, crate::Error> {
+    let (physical_type, logical_type) = match field.data_type() {
+        DataType::Boolean => (PhysicalType::BOOLEAN, LogicalType::NONE),
+        DataType::Int8 => (PhysicalType::INT32, LogicalType::INT_8),
+        DataType::Int16 => (PhysicalType::INT32, LogicalType::INT_16),
+        DataType::Int32 => (PhysicalType::INT32, LogicalType::NONE),
+        DataType::Int64 => (PhysicalType::INT64, LogicalType::NONE),
+        DataType::UInt8 => (PhysicalType::INT32, LogicalType::UINT_8),
+        DataType::UInt16 => (PhysicalType::INT32, LogicalType::UINT_16),
+        DataType::UInt32 => (PhysicalType::INT32, LogicalType::UINT_32),
+        DataType::UInt64 => (PhysicalType::INT64, LogicalType::UINT_64),
+        DataType::Float32 => (PhysicalType::FLOAT, LogicalType::NONE),
+        DataType::Float64 => (PhysicalType::DOUBLE, LogicalType::NONE),
+        DataType::Utf8 => (PhysicalType::BYTE_ARRAY, LogicalType::UTF8),
+        data_type => {
+            return Err(crate::Error::UnknownDataType {
+                data_type: data_type.clone(),
+            })
+        }
+    };
+
+    let repetition = if optional {
+        Repetition::OPTIONAL
+    } else {
+        Repetition::REQUIRED
+    };
+
+    let parquet_type = Type::primitive_type_builder(field.name(), physical_type)
+        .with_repetition(repetition)
+        .with_logical_type(logical_type)
+        .build()?;
+
+    Ok(Rc::new(parquet_type))
+}
+
+/// Read every value of the column chunk, the values only hold the non-null rows
+fn read_values<T: ParquetType>(
+    reader: &mut ColumnReaderImpl<T>,
+    max_def_level: i16,
+    num_rows: usize,
+) -> Result<(Vec<T::T>, Vec<bool>), crate::Error> {
+    let mut values = vec![T::T::default(); num_rows];
+    let mut def_levels = vec![0; num_rows];
+
+    let mut values_read = 0;
+    let mut levels_read = 0;
+
+    while levels_read < num_rows {
+        let levels = if max_def_level > 0 {
+            Some(&mut def_levels[levels_read..])
+        } else {
+            None
+        };
+
+        let (num_values, num_levels) = reader.read_batch(
+            num_rows - levels_read,
+            levels,
+            None,
+            &mut values[values_read..],
+        )?;
+
+        if num_values == 0 && num_levels == 0 {
+            break;
+        }
+
+        values_read += num_values;
+        levels_read += if max_def_level > 0 {
+            num_levels
+        } else {
+            num_values
+        };
+    }
+
+    values.truncate(values_read);
+
+    let valid = if max_def_level > 0 {
+        def_levels[..levels_read]
+            .iter()
+            .map(|level| *level == max_def_level)
+            .collect()
+    } else {
+        vec![true; levels_read]
+    };
+
+    Ok((values, valid))
+}
+
+fn build_primitive<P, V, F>(values: &[V], valid: &[bool], f: F) -> Result<ArrayRef, crate::Error>
+where
+    P: ArrowPrimitiveType,
+    F: Fn(&V) -> P::Native,
+{
+    let mut builder = PrimitiveBuilder::<P>::new(valid.len());
+    let mut values = values.iter();
+
+    for is_valid in valid {
+        match if *is_valid { values.next() } else { None } {
+            Some(value) => builder.append_value(f(value))?,
+            None => builder.append_null()?,
+        }
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+fn build_utf8(values: &[ByteArray], valid: &[bool]) -> Result<ArrayRef, crate::Error> {
+    let mut builder = BinaryBuilder::new(valid.len());
+    let mut values = values.iter();
+
+    for is_valid in valid {
+        match if *is_valid { values.next() } else { None } {
+            Some(value) => builder.append_value(value.data())?,
+            None => builder.append_null()?,
+        }
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+macro_rules! read_primitive {
+    ($reader:ident, $max_def_level:ident, $num_rows:ident, $ty:ty, $convert:expr) => {{
+        let (values, valid) = read_values(&mut $reader, $max_def_level, $num_rows)?;
+        build_primitive::<$ty, _, _>(&values, &valid, $convert)
+    }};
+}
+
+fn read_column(
+    reader: ColumnReader,
+    data_type: &DataType,
+    max_def_level: i16,
+    num_rows: usize,
+) -> Result<ArrayRef, crate::Error> {
+    match (reader, data_type) {
+        (ColumnReader::BoolColumnReader(mut r), DataType::Boolean) => {
+            read_primitive!(r, max_def_level, num_rows, dt::BooleanType, |v| *v)
+        }
+        (ColumnReader::Int32ColumnReader(mut r), DataType::Int8) => {
+            read_primitive!(r, max_def_level, num_rows, dt::Int8Type, |v| *v as i8)
+        }
+        (ColumnReader::Int32ColumnReader(mut r), DataType::Int16) => {
+            read_primitive!(r, max_def_level, num_rows, dt::Int16Type, |v| *v as i16)
+        }
+        (ColumnReader::Int32ColumnReader(mut r), DataType::Int32) => {
+            read_primitive!(r, max_def_level, num_rows, dt::Int32Type, |v| *v)
+        }
+        (ColumnReader::Int32ColumnReader(mut r), DataType::UInt8) => {
+            read_primitive!(r, max_def_level, num_rows, dt::UInt8Type, |v| *v as u8)
+        }
+        (ColumnReader::Int32ColumnReader(mut r), DataType::UInt16) => {
+            read_primitive!(r, max_def_level, num_rows, dt::UInt16Type, |v| *v as u16)
+        }
+        (ColumnReader::Int32ColumnReader(mut r), DataType::UInt32) => {
+            read_primitive!(r, max_def_level, num_rows, dt::UInt32Type, |v| *v as u32)
+        }
+        (ColumnReader::Int64ColumnReader(mut r), DataType::Int64) => {
+            read_primitive!(r, max_def_level, num_rows, dt::Int64Type, |v| *v)
+        }
+        (ColumnReader::Int64ColumnReader(mut r), DataType::UInt64) => {
+            read_primitive!(r, max_def_level, num_rows, dt::UInt64Type, |v| *v as u64)
+        }
+        (ColumnReader::FloatColumnReader(mut r), DataType::Float32) => {
+            read_primitive!(r, max_def_level, num_rows, dt::Float32Type, |v| *v)
+        }
+        (ColumnReader::DoubleColumnReader(mut r), DataType::Float64) => {
+            read_primitive!(r, max_def_level, num_rows, dt::Float64Type, |v| *v)
+        }
+        (ColumnReader::ByteArrayColumnReader(mut r), DataType::Utf8) => {
+            let (values, valid) = read_values(&mut r, max_def_level, num_rows)?;
+            build_utf8(&values, &valid)
+        }
+        (_, data_type) => Err(crate::Error::UnknownDataType {
+            data_type: data_type.clone(),
+        }),
+    }
+}
+
+fn write_primitive<P, T, F>(
+    writer: &mut ColumnWriterImpl<T>,
+    arr: &ArrayRef,
+    rows: Range<usize>,
+    def_levels: Option<&[i16]>,
+    f: F,
+) -> Result<(), crate::Error>
+where
+    P: ArrowPrimitiveType,
+    T: ParquetType,
+    F: Fn(P::Native) -> T::T,
+{
+    let arr = as_array!(arr, PrimitiveArray<P>)?;
+
+    let values: Vec<T::T> = rows
+        .filter(|i| arr.is_valid(*i))
+        .map(|i| f(arr.value(i)))
+        .collect();
+
+    writer.write_batch(&values, def_levels, None)?;
+
+    Ok(())
+}
+
+fn write_column(
+    writer: &mut ColumnWriter,
+    arr: &ArrayRef,
+    rows: Range<usize>,
+    optional: bool,
+) -> Result<(), crate::Error> {
+    let def_levels: Vec<i16> = rows
+        .clone()
+        .map(|i| if arr.is_valid(i) { 1 } else { 0 })
+        .collect();
+    let def_levels = if optional {
+        Some(def_levels.as_slice())
+    } else {
+        None
+    };
+
+    match (arr.data_type(), writer) {
+        (DataType::Boolean, ColumnWriter::BoolColumnWriter(w)) => {
+            write_primitive::<dt::BooleanType, _, _>(w, arr, rows, def_levels, |v| v)
+        }
+        (DataType::Int8, ColumnWriter::Int32ColumnWriter(w)) => {
+            write_primitive::<dt::Int8Type, _, _>(w, arr, rows, def_levels, i32::from)
+        }
+        (DataType::Int16, ColumnWriter::Int32ColumnWriter(w)) => {
+            write_primitive::<dt::Int16Type, _, _>(w, arr, rows, def_levels, i32::from)
+        }
+        (DataType::Int32, ColumnWriter::Int32ColumnWriter(w)) => {
+            write_primitive::<dt::Int32Type, _, _>(w, arr, rows, def_levels, |v| v)
+        }
+        (DataType::UInt8, ColumnWriter::Int32ColumnWriter(w)) => {
+            write_primitive::<dt::UInt8Type, _, _>(w, arr, rows, def_levels, i32::from)
+        }
+        (DataType::UInt16, ColumnWriter::Int32ColumnWriter(w)) => {
+            write_primitive::<dt::UInt16Type, _, _>(w, arr, rows, def_levels, i32::from)
+        }
+        (DataType::UInt32, ColumnWriter::Int32ColumnWriter(w)) => {
+            write_primitive::<dt::UInt32Type, _, _>(w, arr, rows, def_levels, |v| v as i32)
+        }
+        (DataType::Int64, ColumnWriter::Int64ColumnWriter(w)) => {
+            write_primitive::<dt::Int64Type, _, _>(w, arr, rows, def_levels, |v| v)
+        }
+        (DataType::UInt64, ColumnWriter::Int64ColumnWriter(w)) => {
+            write_primitive::<dt::UInt64Type, _, _>(w, arr, rows, def_levels, |v| v as i64)
+        }
+        (DataType::Float32, ColumnWriter::FloatColumnWriter(w)) => {
+            write_primitive::<dt::Float32Type, _, _>(w, arr, rows, def_levels, |v| v)
+        }
+        (DataType::Float64, ColumnWriter::DoubleColumnWriter(w)) => {
+            write_primitive::<dt::Float64Type, _, _>(w, arr, rows, def_levels, |v| v)
+        }
+        (DataType::Utf8, ColumnWriter::ByteArrayColumnWriter(w)) => {
+            let arr = as_array!(arr, BinaryArray)?;
+
+            let values: Vec<ByteArray> = rows
+                .filter(|i| arr.is_valid(*i))
+                .map(|i| ByteArray::from(arr.value(i).to_vec()))
+                .collect();
+
+            w.write_batch(&values, def_levels, None)?;
+            Ok(())
+        }
+        (data_type, _) => Err(crate::Error::UnknownDataType {
+            data_type: data_type.clone(),
+        }),
+    }
+}
+
+/// The statistics of a column chunk, the min and max are in the data type of the column
+#[derive(Debug)]
+struct ColumnStats<'a> {
+    min: Option<ScalarValue<'a>>,
+    max: Option<ScalarValue<'a>>,
+    null_count: u64,
+}
+
+fn column_stats<'a>(
+    column: &'a ColumnChunkMetaData,
+    data_type: &DataType,
+) -> Option<ColumnStats<'a>> {
+    let stats = column.statistics()?;
+
+    let (min, max) = if !stats.has_min_max_set() {
+        (None, None)
+    } else {
+        match (stats, data_type) {
+            (Statistics::Boolean(s), _) => (
+                Some(ScalarValue::Boolean(*s.min())),
+                Some(ScalarValue::Boolean(*s.max())),
+            ),
+            // unsigned values above the signed range may be ordered as negative numbers
+            (Statistics::Int32(_), DataType::UInt32) | (Statistics::Int64(_), DataType::UInt64) => {
+                (None, None)
+            }
+            (Statistics::Int32(s), _) => (
+                ScalarValue::Int32(*s.min()).cast(data_type),
+                ScalarValue::Int32(*s.max()).cast(data_type),
+            ),
+            (Statistics::Int64(s), _) => (
+                ScalarValue::Int64(*s.min()).cast(data_type),
+                ScalarValue::Int64(*s.max()).cast(data_type),
+            ),
+            (Statistics::Float(s), _) => (
+                Some(ScalarValue::Float32(*s.min())),
+                Some(ScalarValue::Float32(*s.max())),
+            ),
+            (Statistics::Double(s), _) => (
+                Some(ScalarValue::Float64(*s.min())),
+                Some(ScalarValue::Float64(*s.max())),
+            ),
+            (Statistics::ByteArray(s), DataType::Utf8) => (
+                Some(ScalarValue::String(s.min().data().into())),
+                Some(ScalarValue::String(s.max().data().into())),
+            ),
+            _ => (None, None),
+        }
+    };
+
+    Some(ColumnStats {
+        min,
+        max,
+        null_count: stats.null_count(),
+    })
+}
+
+impl<'a> ColumnStats<'a> {
+    /// Whether a value between the min and the max could compare true with the value
+    fn may_compare(&self, op: &CmpOp, value: &ScalarValue) -> bool {
+        let (min, max) = match (&self.min, &self.max) {
+            (Some(min), Some(max)) => (min, max),
+            _ => return true,
+        };
+
+        // NaN is the greatest float but writers leave it out of the statistics, so only the
+        // comparisons which NaN never satisfies can rule out a float row group
+        if min.is_float32() || min.is_float64() {
+            let nan_may_match = match op {
+                CmpOp::Gt | CmpOp::GtEq | CmpOp::NotEq => true,
+                _ => is_nan(value),
+            };
+
+            if nan_may_match {
+                return true;
+            }
+        }
+
+        let (min_order, max_order) = match (order(min, value), order(max, value)) {
+            (Some(min_order), Some(max_order)) => (min_order, max_order),
+            _ => return true,
+        };
+
+        match op {
+            CmpOp::Eq => min_order != Ordering::Greater && max_order != Ordering::Less,
+            CmpOp::NotEq => !(min_order == Ordering::Equal && max_order == Ordering::Equal),
+            CmpOp::Lt => min_order == Ordering::Less,
+            CmpOp::LtEq => min_order != Ordering::Greater,
+            CmpOp::Gt => max_order == Ordering::Greater,
+            CmpOp::GtEq => max_order != Ordering::Less,
+        }
+    }
+}
+
+/// Order the values after widening them to a common type, `None` when they can't be compared
+fn order(lhs: &ScalarValue, rhs: &ScalarValue) -> Option<Ordering> {
+    let lhs_type = lhs.data_type()?;
+    let rhs_type = rhs.data_type()?;
+
+    if lhs_type == rhs_type {
+        return Some(total_cmp(lhs, rhs));
+    } else if is_lossy_promotion(lhs_type, rhs_type) {
+        return Some(total_cmp(&lhs.to_number()?, &rhs.to_number()?));
+    }
+
+    let data_type = promote(lhs_type, rhs_type)?;
+    Some(total_cmp(&lhs.cast(&data_type)?, &rhs.cast(&data_type)?))
+}
+
+/// Whether the row group may hold rows which match the filter, filters which can't be decided from
+/// the statistics keep the row group
+fn may_match(filter: &Filter, stats: &[(&str, Option<ColumnStats>)], num_rows: u64) -> bool {
+    let find = |column: &Column| -> Option<&ColumnStats> {
+        let position = match column {
+            Column::Name(name) => stats.iter().position(|(n, _)| n == name)?,
+            Column::Position(position) => *position,
+        };
+        stats.get(position)?.1.as_ref()
+    };
+
+    match filter {
+        Filter::And(lhs, rhs) => may_match(lhs, stats, num_rows) && may_match(rhs, stats, num_rows),
+        Filter::Or(lhs, rhs) => may_match(lhs, stats, num_rows) || may_match(rhs, stats, num_rows),
+        Filter::IsNull(column) => find(column).map_or(true, |s| s.null_count > 0),
+        Filter::IsNotNull(column) => find(column).map_or(true, |s| s.null_count < num_rows),
+        Filter::Scalar(column, op, value) => match find(column) {
+            Some(s) if value.is_null() => match op {
+                CmpOp::Eq => s.null_count > 0,
+                CmpOp::NotEq => s.null_count < num_rows,
+                _ => true,
+            },
+            Some(s) => s.may_compare(op, value),
+            None => true,
+        },
+        Filter::Between(column, low, high) => find(column).map_or(true, |s| {
+            s.may_compare(&CmpOp::GtEq, low) && s.may_compare(&CmpOp::LtEq, high)
+        }),
+        // a null in the list never makes a row true
+        Filter::In(column, values) => find(column).map_or(true, |s| {
+            values
+                .iter()
+                .any(|value| !value.is_null() && s.may_compare(&CmpOp::Eq, value))
+        }),
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod test_parquet {
+    use super::*;
+    use crate::col;
+    use crate::datasource::fixtures::{rows, sample};
+    use ::parquet::schema::parser::parse_message_type;
+    use ::parquet::schema::types::SchemaDescriptor;
+    use arrow::array::Int32Array;
+
+    fn write_sample(name: &str) -> String {
+        let path = std::env::temp_dir().join(name);
+        let path = path.to_str().unwrap().to_string();
+
+        ParquetWriter::with_options(ParquetWriteOptions::new().row_group_size(2))
+            .write_path(&sample(), &path)
+            .unwrap();
+
+        path
+    }
+
+    #[test]
+    fn it_round_trips_parquet() {
+        let path = write_sample("alpine_parquet_round_trip.parquet");
+        let df = ParquetReader::from_path(&path).unwrap();
+
+        assert_eq!(df.view().fields(), sample().fields());
+        assert_eq!(rows(df.view()), rows(&sample()));
+        assert_eq!(
+            df.view().chunked_column(&col!("i32")).unwrap().num_chunks(),
+            2
+        );
+    }
+
+    #[test]
+    fn it_reads_projections_and_row_groups() {
+        let path = write_sample("alpine_parquet_projection.parquet");
+
+        let options = ParquetReadOptions::new()
+            .projection(vec![11, 6])
+            .row_groups(vec![1, 0]);
+        let df = ParquetReader::with_options(options)
+            .read_path(&path)
+            .unwrap();
+
+        let names: Vec<&String> = df.view().fields().iter().map(|f| f.name()).collect();
+        assert_eq!(names, vec!["name", "i32"]);
+
+        let i32s = df.view().column(&col!("i32")).unwrap();
+        assert_eq!(i32s.data(), Int32Array::from(vec![1, -1, 0]).data());
+
+        let options = ParquetReadOptions::new().projection(vec![12]);
+        assert!(ParquetReader::with_options(options)
+            .read_path(&path)
+            .is_err());
+    }
+
+    #[test]
+    fn it_filters_with_pushdown() {
+        let path = write_sample("alpine_parquet_filter.parquet");
+
+        // the first row group holds 0 at most, so it is skipped
+        let filter = Filter::Scalar(col!("i32"), CmpOp::Gt, ScalarValue::Int64(0));
+        let options = ParquetReadOptions::new().filter(filter);
+        let df = ParquetReader::with_options(options)
+            .read_path(&path)
+            .unwrap();
+
+        let i32s = df.view().column(&col!("i32")).unwrap();
+        assert_eq!(i32s.data(), Int32Array::from(vec![1]).data());
+
+        let filter = Filter::Or(
+            Box::new(Filter::Scalar(
+                col!("i32"),
+                CmpOp::Gt,
+                ScalarValue::Int64(0),
+            )),
+            Box::new(Filter::Scalar(
+                col!("name"),
+                CmpOp::Eq,
+                ScalarValue::String("a".into()),
+            )),
+        );
+        let options = ParquetReadOptions::new().filter(filter);
+        let df = ParquetReader::with_options(options)
+            .read_path(&path)
+            .unwrap();
+
+        let i32s = df.view().column(&col!("i32")).unwrap();
+        assert_eq!(i32s.data(), Int32Array::from(vec![-1, 1]).data());
+    }
+
+    #[test]
+    fn it_filters_with_the_query_options() {
+        use crate::ops::cmp::NanOrdering;
+
+        let path = write_sample("alpine_parquet_filter_nans.parquet");
+        let filter = || Filter::Scalar(col!("f64"), CmpOp::Gt, ScalarValue::Float64(0.2));
+
+        // by default the NaN in the second row group is the greatest value
+        let options = ParquetReadOptions::new().filter(filter());
+        let df = ParquetReader::with_options(options)
+            .read_path(&path)
+            .unwrap();
+        assert_eq!(df.view().num_rows(), 1);
+
+        let options = ParquetReadOptions::new()
+            .filter(filter())
+            .query_options(QueryOptions {
+                nans: NanOrdering::Null,
+                ..QueryOptions::default()
+            });
+        let df = ParquetReader::with_options(options)
+            .read_path(&path)
+            .unwrap();
+        assert_eq!(df.view().num_rows(), 0);
+    }
+
+    #[test]
+    fn it_names_nested_columns_by_path() {
+        let message = "
+            message schema {
+                OPTIONAL group a {
+                    REQUIRED INT32 x;
+                }
+                REQUIRED group b {
+                    REQUIRED INT32 x;
+                }
+                REPEATED INT64 values;
+            }
+        ";
+        let schema = SchemaDescriptor::new(Rc::new(parse_message_type(message).unwrap()));
+
+        let a = arrow_field(&schema.column(0)).unwrap();
+        assert_eq!(a, Field::new("a.x", DataType::Int32, true));
+
+        let b = arrow_field(&schema.column(1)).unwrap();
+        assert_eq!(b, Field::new("b.x", DataType::Int32, false));
+
+        assert!(arrow_field(&schema.column(2)).is_err());
+    }
+
+    #[test]
+    fn it_skips_row_groups_from_statistics() {
+        let stats = vec![
+            (
+                "id",
+                Some(ColumnStats {
+                    min: Some(ScalarValue::Int32(10)),
+                    max: Some(ScalarValue::Int32(20)),
+                    null_count: 0,
+                }),
+            ),
+            (
+                "price",
+                Some(ColumnStats {
+                    min: Some(ScalarValue::Float64(1.0)),
+                    max: Some(ScalarValue::Float64(2.0)),
+                    null_count: 3,
+                }),
+            ),
+            ("name", None),
+        ];
+
+        let scalar = |column: &str, op: CmpOp, value: ScalarValue<'static>| {
+            Filter::Scalar(col!(column), op, value)
+        };
+
+        assert!(may_match(
+            &scalar("id", CmpOp::Eq, ScalarValue::Int64(15)),
+            &stats,
+            5
+        ));
+        assert!(!may_match(
+            &scalar("id", CmpOp::Eq, ScalarValue::Int64(25)),
+            &stats,
+            5
+        ));
+        assert!(!may_match(
+            &scalar("id", CmpOp::Lt, ScalarValue::UInt8(10)),
+            &stats,
+            5
+        ));
+        assert!(may_match(
+            &scalar("id", CmpOp::LtEq, ScalarValue::UInt8(10)),
+            &stats,
+            5
+        ));
+        assert!(!may_match(
+            &scalar("id", CmpOp::Gt, ScalarValue::Int32(20)),
+            &stats,
+            5
+        ));
+        assert!(!may_match(
+            &scalar("id", CmpOp::Eq, ScalarValue::Null),
+            &stats,
+            5
+        ));
+        assert!(may_match(
+            &scalar("price", CmpOp::Eq, ScalarValue::Null),
+            &stats,
+            5
+        ));
+
+        // NaN isn't in the statistics, so a greater than can't be ruled out for floats
+        assert!(may_match(
+            &scalar("price", CmpOp::Gt, ScalarValue::Float64(9.0)),
+            &stats,
+            5
+        ));
+        assert!(!may_match(
+            &scalar("price", CmpOp::Lt, ScalarValue::Float64(1.0)),
+            &stats,
+            5
+        ));
+
+        assert!(may_match(
+            &scalar("name", CmpOp::Eq, ScalarValue::String("a".into())),
+            &stats,
+            5
+        ));
+        assert!(may_match(
+            &scalar("missing", CmpOp::Eq, ScalarValue::Int32(1)),
+            &stats,
+            5
+        ));
+
+        let between = Filter::Between(col!("id"), ScalarValue::Int32(21), ScalarValue::Int32(30));
+        assert!(!may_match(&between, &stats, 5));
+
+        let within = Filter::In(col!(0), vec![ScalarValue::Null, ScalarValue::Int32(12)]);
+        assert!(may_match(&within, &stats, 5));
+
+        let and = Filter::And(
+            Box::new(scalar("id", CmpOp::Gt, ScalarValue::Int32(12))),
+            Box::new(Filter::IsNotNull(col!("price"))),
+        );
+        assert!(may_match(&and, &stats, 5));
+        assert!(!may_match(&and, &stats, 3));
+    }
+}
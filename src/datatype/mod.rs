@@ -1,7 +1,16 @@
+use crate::as_array;
 use crate::error::{self as error, Error};
+use crate::utils::is_numeric_type;
+use arrow::array::{
+    Array, ArrayRef, BinaryArray, BooleanArray, Float32Array, Float64Array, Int16Array, Int32Array,
+    Int64Array, Int8Array, UInt16Array, UInt32Array, UInt64Array, UInt8Array,
+};
 use arrow::datatypes::DataType;
 use bstr::BStr;
-use std::fmt::Debug;
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt::{Debug, Display};
+use std::sync::Arc;
 
 use snafu::ensure;
 
@@ -78,6 +87,81 @@ impl<'a> ScalarValue<'a> {
         self == &ScalarValue::Null
     }
 
+    /// Convert a numeric value into another numeric data type, intended for the widening casts
+    /// produced by `promote`. An integer which doesn't fit in the data type produces `None`.
+    pub fn cast(&self, data_type: &DataType) -> Option<ScalarValue<'a>> {
+        if self.data_type() == Some(data_type) {
+            return Some(self.clone());
+        }
+
+        match (self.to_number()?, data_type) {
+            (Number::Int(v), DataType::UInt8) => u8::try_from(v).ok().map(Self::UInt8),
+            (Number::Int(v), DataType::UInt16) => u16::try_from(v).ok().map(Self::UInt16),
+            (Number::Int(v), DataType::UInt32) => u32::try_from(v).ok().map(Self::UInt32),
+            (Number::Int(v), DataType::UInt64) => u64::try_from(v).ok().map(Self::UInt64),
+            (Number::Int(v), DataType::Int8) => i8::try_from(v).ok().map(Self::Int8),
+            (Number::Int(v), DataType::Int16) => i16::try_from(v).ok().map(Self::Int16),
+            (Number::Int(v), DataType::Int32) => i32::try_from(v).ok().map(Self::Int32),
+            (Number::Int(v), DataType::Int64) => i64::try_from(v).ok().map(Self::Int64),
+            (Number::Int(v), DataType::Float32) => Some(Self::Float32(v as f32)),
+            (Number::Int(v), DataType::Float64) => Some(Self::Float64(v as f64)),
+            (Number::Float(v), DataType::Float32) => Some(Self::Float32(v as f32)),
+            (Number::Float(v), DataType::Float64) => Some(Self::Float64(v)),
+            _ => None,
+        }
+    }
+
+    /// The numeric value without rounding, `None` unless the value is numeric
+    pub fn to_number(&self) -> Option<Number> {
+        let number = match self {
+            Self::UInt8(v) => Number::Int(*v as i128),
+            Self::UInt16(v) => Number::Int(*v as i128),
+            Self::UInt32(v) => Number::Int(*v as i128),
+            Self::UInt64(v) => Number::Int(*v as i128),
+            Self::Int8(v) => Number::Int(*v as i128),
+            Self::Int16(v) => Number::Int(*v as i128),
+            Self::Int32(v) => Number::Int(*v as i128),
+            Self::Int64(v) => Number::Int(*v as i128),
+            Self::Float32(v) => Number::Float(*v as f64),
+            Self::Float64(v) => Number::Float(*v),
+            _ => return None,
+        };
+
+        Some(number)
+    }
+
+    /// Broadcast the value into an array of `len` elements, a null produces a boolean array of
+    /// nulls
+    pub fn to_array(&self, len: usize) -> Result<ArrayRef, Error> {
+        let array: ArrayRef = match self {
+            Self::Boolean(v) => Arc::new(BooleanArray::from(vec![*v; len])),
+
+            Self::UInt8(v) => Arc::new(UInt8Array::from(vec![*v; len])),
+            Self::UInt16(v) => Arc::new(UInt16Array::from(vec![*v; len])),
+            Self::UInt32(v) => Arc::new(UInt32Array::from(vec![*v; len])),
+            Self::UInt64(v) => Arc::new(UInt64Array::from(vec![*v; len])),
+
+            Self::Int8(v) => Arc::new(Int8Array::from(vec![*v; len])),
+            Self::Int16(v) => Arc::new(Int16Array::from(vec![*v; len])),
+            Self::Int32(v) => Arc::new(Int32Array::from(vec![*v; len])),
+            Self::Int64(v) => Arc::new(Int64Array::from(vec![*v; len])),
+
+            Self::Float32(v) => Arc::new(Float32Array::from(vec![*v; len])),
+            Self::Float64(v) => Arc::new(Float64Array::from(vec![*v; len])),
+
+            Self::String(v) => {
+                let bytes: &[u8] = v;
+                Arc::new(BinaryArray::from(vec![bytes; len]))
+            }
+
+            Self::Null => Arc::new(BooleanArray::from(vec![None; len])),
+
+            Self::Binary(_) => return Err(Error::WrongType),
+        };
+
+        Ok(array)
+    }
+
     pub fn as_uint8(&self) -> Option<u8> {
         match self {
             Self::UInt8(v) => Some(*v),
@@ -231,6 +315,281 @@ impl<'a> ScalarValue<'a> {
             _ => false,
         }
     }
+
+    pub fn as_string(&self) -> Option<&'a BStr> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn is_string(&self) -> bool {
+        match self {
+            Self::String(_) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<'a> Display for ScalarValue<'a> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            Self::Boolean(v) => write!(fmt, "{}", v),
+
+            Self::UInt8(v) => write!(fmt, "{}", v),
+            Self::UInt16(v) => write!(fmt, "{}", v),
+            Self::UInt32(v) => write!(fmt, "{}", v),
+            Self::UInt64(v) => write!(fmt, "{}", v),
+
+            Self::Int8(v) => write!(fmt, "{}", v),
+            Self::Int16(v) => write!(fmt, "{}", v),
+            Self::Int32(v) => write!(fmt, "{}", v),
+            Self::Int64(v) => write!(fmt, "{}", v),
+
+            Self::Float32(v) => write!(fmt, "{}", v),
+            Self::Float64(v) => write!(fmt, "{}", v),
+
+            Self::Binary(v) => write!(fmt, "{:?}", v),
+            Self::String(v) => write!(fmt, "{}", v),
+
+            Self::Null => write!(fmt, "null"),
+        }
+    }
+}
+
+/// A numeric value of any data type. Integers are held exactly, so values of different data types
+/// are compared without the rounding of a cast. NaN is the only value which isn't ordered.
+#[derive(Clone, Copy, Debug)]
+pub enum Number {
+    Int(i128),
+    Float(f64),
+}
+
+/// 2^127, every `i128` lies in `-I128_BOUND..I128_BOUND`
+const I128_BOUND: f64 = std::i128::MAX as f64;
+
+impl Number {
+    pub fn is_nan(&self) -> bool {
+        match self {
+            Number::Float(v) => v.is_nan(),
+            Number::Int(_) => false,
+        }
+    }
+
+    /// A byte key which two numbers share only when they are equal, an integral float is keyed as
+    /// the integer and every NaN shares one key
+    pub fn key(&self) -> Vec<u8> {
+        match *self {
+            Number::Float(v) if v.is_nan() => vec![2],
+            Number::Float(v) if v.trunc() == v && v.abs() < I128_BOUND => {
+                Number::Int(v as i128).key()
+            }
+            Number::Float(v) => {
+                let mut key = vec![1];
+                key.extend_from_slice(&v.to_bits().to_le_bytes());
+                key
+            }
+            Number::Int(v) => {
+                let mut key = vec![0];
+                key.extend_from_slice(&v.to_le_bytes());
+                key
+            }
+        }
+    }
+}
+
+impl PartialEq for Number {
+    fn eq(&self, other: &Number) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Number) -> Option<Ordering> {
+        match (*self, *other) {
+            (Number::Int(lhs), Number::Int(rhs)) => Some(lhs.cmp(&rhs)),
+            (Number::Float(lhs), Number::Float(rhs)) => lhs.partial_cmp(&rhs),
+            (Number::Int(lhs), Number::Float(rhs)) => cmp_int_float(lhs, rhs),
+            (Number::Float(lhs), Number::Int(rhs)) => {
+                cmp_int_float(rhs, lhs).map(Ordering::reverse)
+            }
+        }
+    }
+}
+
+/// Compare by the integral part of the float and then by its fraction, the float is never rounded
+fn cmp_int_float(int: i128, float: f64) -> Option<Ordering> {
+    if float.is_nan() {
+        return None;
+    } else if float >= I128_BOUND {
+        return Some(Ordering::Less);
+    } else if float < -I128_BOUND {
+        return Some(Ordering::Greater);
+    }
+
+    let trunc = float.trunc();
+    Some(int.cmp(&(trunc as i128)).then(trunc.partial_cmp(&float)?))
+}
+
+macro_rules! to_numbers {
+    ($arr:ident, $( [$dt:path, $ty:ty, $variant:ident, $as:ty] ),*) => {
+        match $arr.data_type() {
+            $($dt => {
+                let arr = as_array!($arr, $ty)?;
+                Ok((0..arr.len()).map(|i| Number::$variant(arr.value(i) as $as)).collect())
+            },)+
+            data_type => Err(Error::UnknownDataType {
+                data_type: data_type.clone(),
+            }),
+        }
+    };
+}
+
+/// The values of a numeric array as numbers, the number of a null row is unspecified
+pub fn to_numbers(arr: &ArrayRef) -> Result<Vec<Number>, Error> {
+    to_numbers!(
+        arr,
+        [DataType::UInt8, UInt8Array, Int, i128],
+        [DataType::UInt16, UInt16Array, Int, i128],
+        [DataType::UInt32, UInt32Array, Int, i128],
+        [DataType::UInt64, UInt64Array, Int, i128],
+        [DataType::Int8, Int8Array, Int, i128],
+        [DataType::Int16, Int16Array, Int, i128],
+        [DataType::Int32, Int32Array, Int, i128],
+        [DataType::Int64, Int64Array, Int, i128],
+        [DataType::Float32, Float32Array, Float, f64],
+        [DataType::Float64, Float64Array, Float, f64]
+    )
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum NumericKind {
+    Unsigned,
+    Signed,
+    Float,
+}
+
+fn numeric_kind(data_type: &DataType) -> Option<(NumericKind, usize)> {
+    match data_type {
+        DataType::UInt8 => Some((NumericKind::Unsigned, 8)),
+        DataType::UInt16 => Some((NumericKind::Unsigned, 16)),
+        DataType::UInt32 => Some((NumericKind::Unsigned, 32)),
+        DataType::UInt64 => Some((NumericKind::Unsigned, 64)),
+        DataType::Int8 => Some((NumericKind::Signed, 8)),
+        DataType::Int16 => Some((NumericKind::Signed, 16)),
+        DataType::Int32 => Some((NumericKind::Signed, 32)),
+        DataType::Int64 => Some((NumericKind::Signed, 64)),
+        DataType::Float32 => Some((NumericKind::Float, 32)),
+        DataType::Float64 => Some((NumericKind::Float, 64)),
+        _ => None,
+    }
+}
+
+fn numeric_type(kind: NumericKind, bits: usize) -> DataType {
+    match (kind, bits) {
+        (NumericKind::Unsigned, 8) => DataType::UInt8,
+        (NumericKind::Unsigned, 16) => DataType::UInt16,
+        (NumericKind::Unsigned, 32) => DataType::UInt32,
+        (NumericKind::Unsigned, _) => DataType::UInt64,
+        (NumericKind::Signed, 8) => DataType::Int8,
+        (NumericKind::Signed, 16) => DataType::Int16,
+        (NumericKind::Signed, 32) => DataType::Int32,
+        (NumericKind::Signed, _) => DataType::Int64,
+        (NumericKind::Float, 32) => DataType::Float32,
+        (NumericKind::Float, _) => DataType::Float64,
+    }
+}
+
+/// The smallest numeric data type which both data types can be widened to. Integers of the same
+/// signedness widen to the larger width and mixed signedness widens to a signed integer large
+/// enough for both. UInt64 mixed with a signed integer, and integers mixed with floats, widen to
+/// a float which rounds the 64 bit integers, see `is_lossy_promotion`. Returns `None` unless both
+/// data types are numeric.
+pub fn promote(lhs: &DataType, rhs: &DataType) -> Option<DataType> {
+    if !is_numeric_type(lhs) || !is_numeric_type(rhs) {
+        return None;
+    }
+
+    if lhs == rhs {
+        return Some(lhs.clone());
+    }
+
+    let (lhs_kind, lhs_bits) = numeric_kind(lhs)?;
+    let (rhs_kind, rhs_bits) = numeric_kind(rhs)?;
+
+    let data_type = match (lhs_kind, rhs_kind) {
+        (lhs_kind, rhs_kind) if lhs_kind == rhs_kind => {
+            numeric_type(lhs_kind, lhs_bits.max(rhs_bits))
+        }
+        (NumericKind::Float, _) | (_, NumericKind::Float) => {
+            let (float_bits, int_bits) = if lhs_kind == NumericKind::Float {
+                (lhs_bits, rhs_bits)
+            } else {
+                (rhs_bits, lhs_bits)
+            };
+
+            // a float32 can only represent the integers of up to 16 bits exactly
+            if float_bits == 32 && int_bits <= 16 {
+                DataType::Float32
+            } else {
+                DataType::Float64
+            }
+        }
+        _ => {
+            let (signed_bits, unsigned_bits) = if lhs_kind == NumericKind::Signed {
+                (lhs_bits, rhs_bits)
+            } else {
+                (rhs_bits, lhs_bits)
+            };
+
+            if signed_bits > unsigned_bits {
+                numeric_type(NumericKind::Signed, signed_bits)
+            } else if unsigned_bits < 64 {
+                numeric_type(NumericKind::Signed, unsigned_bits * 2)
+            } else {
+                DataType::Float64
+            }
+        }
+    };
+
+    Some(data_type)
+}
+
+/// Whether widening to the data type of `promote` rounds some of the values of either data type,
+/// such values must be compared as a `Number` instead
+pub fn is_lossy_promotion(lhs: &DataType, rhs: &DataType) -> bool {
+    let ((lhs_kind, lhs_bits), (rhs_kind, rhs_bits)) = match (numeric_kind(lhs), numeric_kind(rhs))
+    {
+        (Some(lhs), Some(rhs)) => (lhs, rhs),
+        _ => return false,
+    };
+
+    match (lhs_kind, rhs_kind) {
+        (lhs_kind, rhs_kind) if lhs_kind == rhs_kind => false,
+        // a float64 only holds the integers of up to 53 bits exactly
+        (NumericKind::Float, _) => rhs_bits == 64,
+        (_, NumericKind::Float) => lhs_bits == 64,
+        (NumericKind::Unsigned, _) => lhs_bits == 64,
+        (_, NumericKind::Unsigned) => rhs_bits == 64,
+        _ => false,
+    }
+}
+
+/// Cast the array unless it already has the data type
+pub fn cast_array(arr: &ArrayRef, data_type: &DataType) -> Result<ArrayRef, Error> {
+    if arr.data_type() == data_type {
+        return Ok(arr.clone());
+    }
+    Ok(arrow::compute::cast(arr, data_type)?)
+}
+
+/// Widen two numeric arrays to their common data type, see `promote`. The arrays are returned
+/// unchanged when there isn't a common data type.
+pub fn promote_arrays(lhs: &ArrayRef, rhs: &ArrayRef) -> Result<(ArrayRef, ArrayRef), Error> {
+    match promote(lhs.data_type(), rhs.data_type()) {
+        Some(data_type) => Ok((cast_array(lhs, &data_type)?, cast_array(rhs, &data_type)?)),
+        None => Ok((lhs.clone(), rhs.clone())),
+    }
 }
 
 pub fn from_bytes<T: Debug + Copy>(bytes: &[u8]) -> Result<&T, Error> {
@@ -268,4 +627,81 @@ mod test_scalars {
         let out = from_bytes::<u64>(&array_bytes[0..8]).unwrap();
         assert_eq!(out, &1024);
     }
+
+    #[test]
+    fn test_promote() {
+        let cases = vec![
+            (DataType::UInt8, DataType::UInt8, Some(DataType::UInt8)),
+            (DataType::UInt8, DataType::UInt32, Some(DataType::UInt32)),
+            (DataType::Int16, DataType::Int64, Some(DataType::Int64)),
+            (DataType::UInt8, DataType::Int32, Some(DataType::Int32)),
+            (DataType::UInt32, DataType::Int32, Some(DataType::Int64)),
+            (DataType::UInt64, DataType::Int8, Some(DataType::Float64)),
+            (DataType::Int16, DataType::Float32, Some(DataType::Float32)),
+            (DataType::Int32, DataType::Float32, Some(DataType::Float64)),
+            (
+                DataType::Float32,
+                DataType::Float64,
+                Some(DataType::Float64),
+            ),
+            (DataType::Int32, DataType::Utf8, None),
+            (DataType::Boolean, DataType::Boolean, None),
+        ];
+
+        for (lhs, rhs, expected) in cases {
+            assert_eq!(promote(&lhs, &rhs), expected);
+            assert_eq!(promote(&rhs, &lhs), expected);
+        }
+    }
+
+    #[test]
+    fn test_lossy_promotion() {
+        assert!(is_lossy_promotion(&DataType::UInt64, &DataType::Int8));
+        assert!(is_lossy_promotion(&DataType::Float32, &DataType::Int64));
+        assert!(!is_lossy_promotion(&DataType::Int32, &DataType::Float64));
+        assert!(!is_lossy_promotion(&DataType::UInt64, &DataType::UInt8));
+        assert!(!is_lossy_promotion(&DataType::Utf8, &DataType::Int64));
+    }
+
+    #[test]
+    fn test_compare_numbers() {
+        let big = Number::Int(9_007_199_254_740_993);
+        assert!(big > Number::Float(9_007_199_254_740_992.0));
+        assert!(Number::Int(-3) < Number::Float(-2.5));
+        assert!(Number::Int(-2) > Number::Float(-2.5));
+        assert!(Number::Int(std::i64::MIN as i128) > Number::Float(std::f64::NEG_INFINITY));
+        assert!(Number::Int(1) == Number::Float(1.0));
+        assert_eq!(
+            Number::Int(1).partial_cmp(&Number::Float(std::f64::NAN)),
+            None
+        );
+
+        assert_eq!(Number::Int(0).key(), Number::Float(-0.0).key());
+        assert_ne!(big.key(), Number::Float(9_007_199_254_740_992.0).key());
+    }
+
+    #[test]
+    fn test_cast_scalar() {
+        assert_eq!(
+            ScalarValue::Int8(-3).cast(&DataType::Int64),
+            Some(ScalarValue::Int64(-3))
+        );
+        assert_eq!(
+            ScalarValue::UInt16(7).cast(&DataType::Float32),
+            Some(ScalarValue::Float32(7.0))
+        );
+        assert_eq!(ScalarValue::Boolean(true).cast(&DataType::Int8), None);
+
+        // narrowing keeps values which fit and rejects the rest instead of wrapping
+        assert_eq!(
+            ScalarValue::Int64(-3).cast(&DataType::Int8),
+            Some(ScalarValue::Int8(-3))
+        );
+        assert_eq!(ScalarValue::UInt16(300).cast(&DataType::UInt8), None);
+        assert_eq!(
+            ScalarValue::UInt64(std::u64::MAX).cast(&DataType::Int64),
+            None
+        );
+        assert_eq!(ScalarValue::Int32(-1).cast(&DataType::UInt32), None);
+    }
 }
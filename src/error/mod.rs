@@ -19,7 +19,14 @@ pub enum Error {
         index: usize,
         len: usize,
     },
+    InvalidColumn {
+        column: crate::column::Column,
+    },
     WrongType,
+    Overflow {
+        op: String,
+        data_type: DataType,
+    },
     UnknownDataType {
         data_type: arrow::datatypes::DataType,
     },
@@ -47,9 +54,33 @@ pub enum Error {
     FilterError {
         source: crate::query::filter::Error,
     },
+    JoinError {
+        source: crate::query::join::Error,
+    },
     ComparisonError {
         source: crate::ops::cmp::Error,
     },
+    SortError {
+        source: crate::ops::sort::Error,
+    },
+    ExprError {
+        source: crate::expr::Error,
+    },
+    StringError {
+        source: crate::ops::string::Error,
+    },
+    CsvError {
+        source: crate::datasource::csv::Error,
+    },
+    JsonError {
+        source: crate::datasource::json::Error,
+    },
+    ChunkedError {
+        source: crate::chunked::Error,
+    },
+    Parquet {
+        inner: String,
+    },
 }
 
 macro_rules! impl_from_source {
@@ -67,4 +98,11 @@ impl_from_source!(std::io::Error, Error::Io);
 impl_from_source!(std::num::TryFromIntError, Error::FromIntError);
 impl_from_source!(crate::query::select::Error, Error::SelectError);
 impl_from_source!(crate::query::filter::Error, Error::FilterError);
+impl_from_source!(crate::query::join::Error, Error::JoinError);
 impl_from_source!(crate::ops::cmp::Error, Error::ComparisonError);
+impl_from_source!(crate::ops::sort::Error, Error::SortError);
+impl_from_source!(crate::expr::Error, Error::ExprError);
+impl_from_source!(crate::ops::string::Error, Error::StringError);
+impl_from_source!(crate::datasource::csv::Error, Error::CsvError);
+impl_from_source!(crate::datasource::json::Error, Error::JsonError);
+impl_from_source!(crate::chunked::Error, Error::ChunkedError);
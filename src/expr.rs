@@ -1 +1,597 @@
+use crate::column::Column;
+use crate::datatype::ScalarValue;
+use crate::ops::string::{self, Pattern};
+use crate::ops::{boolean, cmp, math};
+use crate::query::select::{Arithmetic, Unary};
+use crate::query::QueryOptions;
+use crate::utils::is_integer_type;
+use crate::{ArrayRef, DataType, View};
+use arrow::array::{Array, BooleanArray};
+use bstr::ByteSlice;
+use snafu::Snafu;
+use std::fmt::Display;
+use std::sync::Arc;
 
+pub use crate::ops::cmp::CmpOp;
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("The column does not exist {}", column))]
+    InvalidColumn { column: Column },
+
+    #[snafu(display("Expected a boolean expression, found the data type {:?}", data_type))]
+    InvalidBooleanType { data_type: DataType },
+
+    #[snafu(display("The function {} does not exist", name))]
+    UnknownFunction { name: String },
+
+    #[snafu(display("Invalid arguments for the function {}, expected {}", name, expected))]
+    InvalidArguments {
+        name: String,
+        expected: &'static str,
+    },
+}
+
+/// An expression tree which is evaluated against the columns of a view, e.g.
+/// `(col("a") + col("b")) * lit(ScalarValue::Int64(2))`
+#[derive(Clone, Debug)]
+pub enum Expr<'a> {
+    Column(Column),
+    Literal(ScalarValue<'a>),
+    Arithmetic(Arc<Expr<'a>>, Arithmetic, Arc<Expr<'a>>),
+    Unary(Unary, Arc<Expr<'a>>),
+    Comparison(Arc<Expr<'a>>, CmpOp, Arc<Expr<'a>>),
+    And(Arc<Expr<'a>>, Arc<Expr<'a>>),
+    Or(Arc<Expr<'a>>, Arc<Expr<'a>>),
+    Not(Arc<Expr<'a>>),
+    IsNull(Arc<Expr<'a>>),
+    IsNotNull(Arc<Expr<'a>>),
+    Function(&'a str, Vec<Expr<'a>>),
+}
+
+pub fn col<C: Into<Column>>(column: C) -> Expr<'static> {
+    Expr::Column(column.into())
+}
+
+pub fn lit(value: ScalarValue<'_>) -> Expr<'_> {
+    Expr::Literal(value)
+}
+
+impl<'a> Expr<'a> {
+    pub fn eq(self, rhs: Expr<'a>) -> Expr<'a> {
+        self.compare(CmpOp::Eq, rhs)
+    }
+
+    pub fn not_eq(self, rhs: Expr<'a>) -> Expr<'a> {
+        self.compare(CmpOp::NotEq, rhs)
+    }
+
+    pub fn gt(self, rhs: Expr<'a>) -> Expr<'a> {
+        self.compare(CmpOp::Gt, rhs)
+    }
+
+    pub fn gt_eq(self, rhs: Expr<'a>) -> Expr<'a> {
+        self.compare(CmpOp::GtEq, rhs)
+    }
+
+    pub fn lt(self, rhs: Expr<'a>) -> Expr<'a> {
+        self.compare(CmpOp::Lt, rhs)
+    }
+
+    pub fn lt_eq(self, rhs: Expr<'a>) -> Expr<'a> {
+        self.compare(CmpOp::LtEq, rhs)
+    }
+
+    pub fn compare(self, op: CmpOp, rhs: Expr<'a>) -> Expr<'a> {
+        Expr::Comparison(Arc::new(self), op, Arc::new(rhs))
+    }
+
+    pub fn and(self, rhs: Expr<'a>) -> Expr<'a> {
+        Expr::And(Arc::new(self), Arc::new(rhs))
+    }
+
+    pub fn or(self, rhs: Expr<'a>) -> Expr<'a> {
+        Expr::Or(Arc::new(self), Arc::new(rhs))
+    }
+
+    pub fn is_null(self) -> Expr<'a> {
+        Expr::IsNull(Arc::new(self))
+    }
+
+    pub fn is_not_null(self) -> Expr<'a> {
+        Expr::IsNotNull(Arc::new(self))
+    }
+
+    pub fn arithmetic(self, op: Arithmetic, rhs: Expr<'a>) -> Expr<'a> {
+        Expr::Arithmetic(Arc::new(self), op, Arc::new(rhs))
+    }
+
+    pub fn abs(self) -> Expr<'a> {
+        Expr::Unary(Unary::Abs, Arc::new(self))
+    }
+
+    /// The columns of the view which are referenced by the expression
+    pub fn columns(&self) -> Vec<&Column> {
+        match self {
+            Expr::Column(column) => vec![column],
+            Expr::Literal(_) => vec![],
+            Expr::Arithmetic(lhs, _, rhs)
+            | Expr::Comparison(lhs, _, rhs)
+            | Expr::And(lhs, rhs)
+            | Expr::Or(lhs, rhs) => {
+                let mut columns = lhs.columns();
+                columns.extend(rhs.columns());
+                columns
+            }
+            Expr::Unary(_, expr) | Expr::Not(expr) | Expr::IsNull(expr) | Expr::IsNotNull(expr) => {
+                expr.columns()
+            }
+            Expr::Function(_, args) => args.iter().flat_map(|arg| arg.columns()).collect(),
+        }
+    }
+}
+
+impl<'a> std::ops::Add for Expr<'a> {
+    type Output = Expr<'a>;
+
+    fn add(self, rhs: Expr<'a>) -> Expr<'a> {
+        self.arithmetic(Arithmetic::Add, rhs)
+    }
+}
+
+impl<'a> std::ops::Sub for Expr<'a> {
+    type Output = Expr<'a>;
+
+    fn sub(self, rhs: Expr<'a>) -> Expr<'a> {
+        self.arithmetic(Arithmetic::Sub, rhs)
+    }
+}
+
+impl<'a> std::ops::Mul for Expr<'a> {
+    type Output = Expr<'a>;
+
+    fn mul(self, rhs: Expr<'a>) -> Expr<'a> {
+        self.arithmetic(Arithmetic::Mul, rhs)
+    }
+}
+
+impl<'a> std::ops::Div for Expr<'a> {
+    type Output = Expr<'a>;
+
+    fn div(self, rhs: Expr<'a>) -> Expr<'a> {
+        self.arithmetic(Arithmetic::Div, rhs)
+    }
+}
+
+impl<'a> std::ops::Rem for Expr<'a> {
+    type Output = Expr<'a>;
+
+    fn rem(self, rhs: Expr<'a>) -> Expr<'a> {
+        self.arithmetic(Arithmetic::Mod, rhs)
+    }
+}
+
+impl<'a> std::ops::Neg for Expr<'a> {
+    type Output = Expr<'a>;
+
+    fn neg(self) -> Expr<'a> {
+        Expr::Unary(Unary::Neg, Arc::new(self))
+    }
+}
+
+impl<'a> std::ops::Not for Expr<'a> {
+    type Output = Expr<'a>;
+
+    fn not(self) -> Expr<'a> {
+        Expr::Not(Arc::new(self))
+    }
+}
+
+impl<'a> Display for Expr<'a> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            Expr::Column(Column::Name(name)) => write!(fmt, "{}", name),
+            Expr::Column(column) => write!(fmt, "{}", column),
+            Expr::Literal(value) => write!(fmt, "{}", value),
+            Expr::Arithmetic(lhs, op, rhs) => write!(fmt, "({} {} {})", lhs, op.as_str(), rhs),
+            Expr::Unary(op, expr) => write!(fmt, "{}({})", op.as_str(), expr),
+            Expr::Comparison(lhs, op, rhs) => write!(fmt, "({} {} {})", lhs, op, rhs),
+            Expr::And(lhs, rhs) => write!(fmt, "({} AND {})", lhs, rhs),
+            Expr::Or(lhs, rhs) => write!(fmt, "({} OR {})", lhs, rhs),
+            Expr::Not(expr) => write!(fmt, "NOT {}", expr),
+            Expr::IsNull(expr) => write!(fmt, "{} IS NULL", expr),
+            Expr::IsNotNull(expr) => write!(fmt, "{} IS NOT NULL", expr),
+            Expr::Function(name, args) => {
+                let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+                write!(fmt, "{}({})", name, args.join(", "))
+            }
+        }
+    }
+}
+
+/// Evaluate the expression against the view, producing an array with one element per row
+pub fn evaluate(view: &View, expr: &Expr) -> Result<ArrayRef, crate::Error> {
+    evaluate_with_options(view, expr, &QueryOptions::default())
+}
+
+/// Evaluate the expression, the options decide how the arithmetic handles edge cases such as a
+/// division by zero
+pub fn evaluate_with_options(
+    view: &View,
+    expr: &Expr,
+    options: &QueryOptions,
+) -> Result<ArrayRef, crate::Error> {
+    match expr {
+        Expr::Column(column) => view
+            .chunked_column(column)
+            .ok_or_else(|| Error::InvalidColumn {
+                column: column.clone(),
+            })?
+            .to_array(),
+        Expr::Literal(value) => value.to_array(view.num_rows()),
+        Expr::Arithmetic(lhs, op, rhs) => {
+            let lhs = evaluate_with_options(view, lhs, options)?;
+            let rhs = evaluate_with_options(view, rhs, options)?;
+            math::arithmetic(&lhs, &rhs, op, options.divide_by_zero)
+        }
+        Expr::Unary(op, expr) => {
+            let arr = evaluate_with_options(view, expr, options)?;
+            math::unary(&arr, op)
+        }
+        Expr::Comparison(lhs, op, rhs) => {
+            let lhs = evaluate_with_options(view, lhs, options)?;
+            let rhs = evaluate_with_options(view, rhs, options)?;
+            Ok(Arc::new(cmp::compare(
+                &lhs,
+                &rhs,
+                op.clone(),
+                options.nans,
+            )?))
+        }
+        Expr::And(lhs, rhs) => {
+            let lhs = evaluate_boolean_with_options(view, lhs, options)?;
+            let rhs = evaluate_boolean_with_options(view, rhs, options)?;
+            Ok(Arc::new(boolean::and(&lhs, &rhs)?))
+        }
+        Expr::Or(lhs, rhs) => {
+            let lhs = evaluate_boolean_with_options(view, lhs, options)?;
+            let rhs = evaluate_boolean_with_options(view, rhs, options)?;
+            Ok(Arc::new(boolean::or(&lhs, &rhs)?))
+        }
+        Expr::Not(expr) => {
+            let arr = evaluate_boolean_with_options(view, expr, options)?;
+            Ok(Arc::new(boolean::not(&arr)?))
+        }
+        Expr::IsNull(expr) => {
+            let arr = evaluate_with_options(view, expr, options)?;
+            let values: Vec<bool> = (0..arr.len()).map(|i| arr.is_null(i)).collect();
+            Ok(Arc::new(BooleanArray::from(values)))
+        }
+        Expr::IsNotNull(expr) => {
+            let arr = evaluate_with_options(view, expr, options)?;
+            let values: Vec<bool> = (0..arr.len()).map(|i| arr.is_valid(i)).collect();
+            Ok(Arc::new(BooleanArray::from(values)))
+        }
+        Expr::Function(name, args) => evaluate_function(view, name, args, options),
+    }
+}
+
+fn evaluate_function(
+    view: &View,
+    name: &str,
+    args: &[Expr],
+    options: &QueryOptions,
+) -> Result<ArrayRef, crate::Error> {
+    match name {
+        "contains" | "starts_with" | "ends_with" | "like" | "ilike" | "regexp_match" => {
+            let (arr, pattern) = match args {
+                [expr, pattern] => (
+                    evaluate_with_options(view, expr, options)?,
+                    string_literal(name, pattern)?,
+                ),
+                _ => return Err(invalid_arguments(name, "a string and a pattern")),
+            };
+
+            let pattern = pattern
+                .to_str()
+                .map_err(|_| invalid_arguments(name, "a pattern of valid UTF-8"))?;
+
+            // the name is one of the patterns matched above
+            let pattern = Pattern::from_name(name, pattern).unwrap();
+
+            Ok(Arc::new(string::matches(&arr, &pattern)?))
+        }
+        "upper" | "lower" | "trim" | "length" => {
+            let arr = match args {
+                [expr] => evaluate_with_options(view, expr, options)?,
+                _ => return Err(invalid_arguments(name, "a single string")),
+            };
+
+            match name {
+                "upper" => string::upper(&arr),
+                "lower" => string::lower(&arr),
+                "trim" => string::trim(&arr),
+                _ => string::length(&arr),
+            }
+        }
+        "substring" => {
+            let expected = "a string, a start position and an optional length";
+
+            let (expr, start, len) = match args {
+                [expr, start] => (expr, integer_literal(name, start)?, None),
+                [expr, start, len] => (
+                    expr,
+                    integer_literal(name, start)?,
+                    Some(integer_literal(name, len)?),
+                ),
+                _ => return Err(invalid_arguments(name, expected)),
+            };
+
+            let len = match len {
+                Some(len) if len < 0 => return Err(invalid_arguments(name, expected)),
+                len => len.map(|len| len as u64),
+            };
+
+            let arr = evaluate_with_options(view, expr, options)?;
+            string::substring(&arr, start, len)
+        }
+        "concat" => {
+            if args.is_empty() {
+                return Err(invalid_arguments(name, "at least one string"));
+            }
+
+            let arrays = args
+                .iter()
+                .map(|arg| evaluate_with_options(view, arg, options))
+                .collect::<Result<Vec<ArrayRef>, crate::Error>>()?;
+
+            string::concat(&arrays)
+        }
+        "replace" => match args {
+            [expr, from, to] => {
+                let arr = evaluate_with_options(view, expr, options)?;
+                string::replace(&arr, string_literal(name, from)?, string_literal(name, to)?)
+            }
+            _ => Err(invalid_arguments(
+                name,
+                "a string, the text to replace and its replacement",
+            )),
+        },
+        "split_part" => {
+            let expected = "a string, a delimiter and a part number starting at 1";
+
+            match args {
+                [expr, delimiter, n] => {
+                    let n = integer_literal(name, n)?;
+                    if n < 1 {
+                        return Err(invalid_arguments(name, expected));
+                    }
+
+                    let arr = evaluate_with_options(view, expr, options)?;
+                    string::split_part(&arr, string_literal(name, delimiter)?, n as usize)
+                }
+                _ => Err(invalid_arguments(name, expected)),
+            }
+        }
+        _ => Err(Error::UnknownFunction {
+            name: name.to_string(),
+        }
+        .into()),
+    }
+}
+
+fn invalid_arguments(name: &str, expected: &'static str) -> crate::Error {
+    Error::InvalidArguments {
+        name: name.to_string(),
+        expected,
+    }
+    .into()
+}
+
+/// The bytes of a string literal argument
+fn string_literal<'b>(name: &str, arg: &'b Expr) -> Result<&'b [u8], crate::Error> {
+    match arg {
+        Expr::Literal(ScalarValue::String(s)) => Ok(s),
+        _ => Err(invalid_arguments(name, "a string literal")),
+    }
+}
+
+fn integer_literal(name: &str, arg: &Expr) -> Result<i64, crate::Error> {
+    match arg {
+        Expr::Literal(value) if value.data_type().map_or(false, is_integer_type) => value
+            .cast(&DataType::Int64)
+            .and_then(|value| value.as_int64())
+            .ok_or_else(|| invalid_arguments(name, "an integer literal")),
+        _ => Err(invalid_arguments(name, "an integer literal")),
+    }
+}
+
+/// Evaluate an expression which must produce a boolean array, such as a comparison
+pub fn evaluate_boolean(view: &View, expr: &Expr) -> Result<BooleanArray, crate::Error> {
+    evaluate_boolean_with_options(view, expr, &QueryOptions::default())
+}
+
+pub fn evaluate_boolean_with_options(
+    view: &View,
+    expr: &Expr,
+    options: &QueryOptions,
+) -> Result<BooleanArray, crate::Error> {
+    let arr = evaluate_with_options(view, expr, options)?;
+
+    if arr.data_type() != &DataType::Boolean {
+        return Err(Error::InvalidBooleanType {
+            data_type: arr.data_type().clone(),
+        }
+        .into());
+    }
+
+    Ok(BooleanArray::from(arr.data()))
+}
+
+#[cfg(test)]
+mod test_expr {
+    use super::*;
+    use crate::query::filter::{filter, Filter};
+    use crate::query::select::select;
+    use crate::{array, as_array, sel, view};
+    use arrow::array::{PrimitiveArray, UInt8Array};
+    use arrow::datatypes as dt;
+
+    #[test]
+    fn it_evaluates_nested_arithmetic() {
+        let view = view!(
+            ["a", dt::UInt8Type, [1, 2, 3]],
+            ["b", dt::UInt8Type, [4, 5, 6]]
+        );
+
+        let expr = (col("a") + col("b")) * lit(ScalarValue::UInt8(2));
+        assert_eq!(expr.to_string(), "((a + b) * 2)");
+
+        let res = evaluate(&view, &expr).unwrap();
+        let expected = array!(dt::UInt8Type, [10, 14, 18]);
+        assert_eq!(as_array!(res, UInt8Array).unwrap(), &expected);
+    }
+
+    #[test]
+    fn it_evaluates_boolean_logic() {
+        let view = view!(
+            ["a", dt::UInt8Type, [1, 2, 3, 4]],
+            ["b", dt::UInt8Type, [4, 3, 2, 1]]
+        );
+
+        let expr = (col("a") + col("b"))
+            .eq(lit(ScalarValue::UInt8(5)))
+            .and(!col("a").gt(col("b")))
+            .or(col("a").eq(lit(ScalarValue::UInt8(4))));
+
+        let res = evaluate_boolean(&view, &expr).unwrap();
+        assert_eq!(res, BooleanArray::from(vec![true, true, false, true]));
+    }
+
+    #[test]
+    fn it_selects_and_filters_expressions() {
+        let view = view!(
+            ["a", dt::UInt8Type, [1, 2, 3, 4]],
+            ["b", dt::UInt8Type, [4, 3, 2, 1]]
+        );
+
+        let view = filter(&view, Filter::Expr(col("a").lt_eq(col("b")))).unwrap();
+        let view = select(view, vec![sel!(col("a") * col("b"), "c")]).unwrap();
+
+        let c = view.column(&"c".into()).unwrap();
+        let expected = array!(dt::UInt8Type, [4, 6]);
+        assert_eq!(as_array!(c, UInt8Array).unwrap(), &expected);
+    }
+
+    #[test]
+    fn it_evaluates_unary_and_modulo() {
+        use arrow::array::Int32Array;
+
+        let view = view!(
+            ["a", dt::Int32Type, [5, -6, 7]],
+            ["b", dt::Int32Type, [3, 4, 0]]
+        );
+
+        let expr = -(col("a") % col("b")).abs();
+        assert_eq!(expr.to_string(), "neg(abs((a % b)))");
+
+        assert!(evaluate(&view, &expr).is_err());
+
+        let options = QueryOptions {
+            divide_by_zero: math::DivideByZero::Null,
+            ..QueryOptions::default()
+        };
+        let res = evaluate_with_options(&view, &expr, &options).unwrap();
+        let expected = Int32Array::from(vec![Some(-2), Some(-2), None]);
+        assert_eq!(as_array!(res, Int32Array).unwrap(), &expected);
+    }
+
+    #[test]
+    fn it_evaluates_pattern_functions() {
+        use crate::Field;
+        use arrow::array::BinaryArray;
+
+        let view = View::new(
+            vec![Field::new("level", DataType::Utf8, false)],
+            vec![Arc::new(BinaryArray::from(vec!["ERROR", "warn", "error"])) as ArrayRef],
+        );
+
+        let expr = Expr::Function(
+            "ilike",
+            vec![col("level"), lit(ScalarValue::String("err%".into()))],
+        );
+        let res = evaluate_boolean(&view, &expr).unwrap();
+        assert_eq!(res, BooleanArray::from(vec![true, false, true]));
+
+        let expr = Expr::Function("contains", vec![col("level")]);
+        assert!(evaluate(&view, &expr).is_err());
+    }
+
+    #[test]
+    fn it_selects_string_functions() {
+        use crate::Field;
+        use arrow::array::BinaryArray;
+
+        let view = View::new(
+            vec![
+                Field::new("first", DataType::Utf8, false),
+                Field::new("last", DataType::Utf8, false),
+            ],
+            vec![
+                Arc::new(BinaryArray::from(vec![" ada ", "grace"])) as ArrayRef,
+                Arc::new(BinaryArray::from(vec!["Lovelace", "Hopper"])) as ArrayRef,
+            ],
+        );
+
+        let full_name = Expr::Function(
+            "concat",
+            vec![
+                Expr::Function("upper", vec![Expr::Function("trim", vec![col("first")])]),
+                lit(ScalarValue::String(" ".into())),
+                Expr::Function(
+                    "substring",
+                    vec![
+                        col("last"),
+                        lit(ScalarValue::Int32(1)),
+                        lit(ScalarValue::UInt8(3)),
+                    ],
+                ),
+            ],
+        );
+        assert_eq!(
+            full_name.to_string(),
+            "concat(upper(trim(first)),  , substring(last, 1, 3))"
+        );
+
+        let view = select(view, vec![sel!(full_name, "name")]).unwrap();
+        let name = view.column(&"name".into()).unwrap();
+        let name = as_array!(name, BinaryArray).unwrap();
+        assert_eq!(name.value(0), b"ADA Lov");
+        assert_eq!(name.value(1), b"GRACE Hop");
+    }
+
+    #[test]
+    fn it_rejects_invalid_function_arguments() {
+        let view = view!(["a", dt::UInt8Type, [1, 2, 3]]);
+
+        assert!(evaluate(&view, &Expr::Function("upper", vec![col("a")])).is_err());
+        assert!(evaluate(&view, &Expr::Function("upper", vec![])).is_err());
+        assert!(evaluate(&view, &Expr::Function("nope", vec![col("a")])).is_err());
+
+        // an integer which doesn't fit in an i64 is rejected rather than wrapped to a negative
+        let substring = Expr::Function(
+            "substring",
+            vec![
+                Expr::Function("trim", vec![lit(ScalarValue::String("abc".into()))]),
+                lit(ScalarValue::UInt64(std::u64::MAX)),
+            ],
+        );
+        assert!(evaluate(&view, &substring).is_err());
+    }
+
+    #[test]
+    fn it_rejects_non_boolean_expressions() {
+        let view = view!(["a", dt::UInt8Type, [1, 2, 3]]);
+        assert!(evaluate_boolean(&view, &col("a")).is_err());
+    }
+}
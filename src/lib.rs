@@ -1,3 +1,4 @@
+pub mod chunked;
 pub mod column;
 pub mod dataframe;
 pub mod datasource;
@@ -15,6 +16,7 @@ pub use arrow::{
     array::ArrayRef,
     datatypes::{DataType, Field},
 };
+pub use chunked::ChunkedArray;
 pub use dataframe::DataFrame;
 pub use error::Error;
 pub use view::View;
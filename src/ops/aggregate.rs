@@ -0,0 +1,235 @@
+use crate::ops::cmp::total_cmp;
+use crate::query::select::Aggregate;
+use crate::{as_array, Error};
+use arrow::array::{
+    Array, ArrayRef, BinaryArray, BinaryBuilder, Float32Array, Float64Array, Int16Array,
+    Int32Array, Int64Array, Int8Array, UInt16Array, UInt32Array, UInt64Array, UInt8Array,
+};
+use arrow::datatypes::DataType;
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+macro_rules! min_max {
+    ($arr:ident, $groups:ident, $keep:expr, $( [$dt:path, $ty:ty] ),*) => {
+        match $arr.data_type() {
+            $($dt => {
+                let arr = as_array!($arr, $ty)?;
+                let values: Vec<_> = $groups
+                    .iter()
+                    .map(|group| {
+                        valid(arr, group).fold(None, |acc, i| match acc {
+                            Some(current) if !$keep(arr.value(i), current) => Some(current),
+                            _ => Some(arr.value(i)),
+                        })
+                    })
+                    .collect();
+                Ok(Arc::new(<$ty>::from(values)) as ArrayRef)
+            },)+
+            DataType::Utf8 => {
+                let arr = as_array!($arr, BinaryArray)?;
+                let mut builder = BinaryBuilder::new($groups.len());
+
+                for group in $groups {
+                    let value = valid(arr, group).fold(None, |acc, i| match acc {
+                        Some(current) if !$keep(arr.value(i), current) => Some(current),
+                        _ => Some(arr.value(i)),
+                    });
+
+                    match value {
+                        Some(value) => builder.append_value(value)?,
+                        None => builder.append_null()?,
+                    }
+                }
+
+                Ok(Arc::new(builder.finish()) as ArrayRef)
+            }
+            data_type => Err(Error::UnknownDataType {
+                data_type: data_type.clone(),
+            }),
+        }
+    };
+}
+
+macro_rules! sum {
+    ($arr:ident, $groups:ident, $( [$dt:path, $ty:ty, $acc:ty, $out:ty] ),*) => {
+        match $arr.data_type() {
+            $($dt => {
+                let arr = as_array!($arr, $ty)?;
+                let values = $groups
+                    .iter()
+                    .map(|group| {
+                        valid(arr, group).try_fold(None, |acc: Option<$acc>, i| {
+                            acc.unwrap_or_default()
+                                .checked_sum(arr.value(i) as $acc)
+                                .map(Some)
+                                .ok_or_else(|| Error::Overflow {
+                                    op: "sum".to_string(),
+                                    data_type: $arr.data_type().clone(),
+                                })
+                        })
+                    })
+                    .collect::<Result<Vec<Option<$acc>>, Error>>()?;
+                Ok(Arc::new(<$out>::from(values)) as ArrayRef)
+            },)+
+            data_type => Err(Error::UnknownDataType {
+                data_type: data_type.clone(),
+            }),
+        }
+    };
+}
+
+macro_rules! avg {
+    ($arr:ident, $groups:ident, $( [$dt:path, $ty:ty] ),*) => {
+        match $arr.data_type() {
+            $($dt => {
+                let arr = as_array!($arr, $ty)?;
+                let values: Vec<Option<f64>> = $groups
+                    .iter()
+                    .map(|group| {
+                        let (sum, count) = valid(arr, group)
+                            .fold((0f64, 0usize), |(sum, count), i| {
+                                (sum + arr.value(i) as f64, count + 1)
+                            });
+
+                        if count == 0 {
+                            None
+                        } else {
+                            Some(sum / count as f64)
+                        }
+                    })
+                    .collect();
+                Ok(Arc::new(Float64Array::from(values)) as ArrayRef)
+            },)+
+            data_type => Err(Error::UnknownDataType {
+                data_type: data_type.clone(),
+            }),
+        }
+    };
+}
+
+/// Addition which reports an overflow instead of wrapping, floats never overflow
+trait CheckedSum: Sized {
+    fn checked_sum(self, other: Self) -> Option<Self>;
+}
+
+impl CheckedSum for u64 {
+    fn checked_sum(self, other: u64) -> Option<u64> {
+        self.checked_add(other)
+    }
+}
+
+impl CheckedSum for i64 {
+    fn checked_sum(self, other: i64) -> Option<i64> {
+        self.checked_add(other)
+    }
+}
+
+impl CheckedSum for f64 {
+    fn checked_sum(self, other: f64) -> Option<f64> {
+        Some(self + other)
+    }
+}
+
+/// The indexes of the group which hold a value
+fn valid<'a, A: Array + ?Sized>(
+    arr: &'a A,
+    group: &'a [usize],
+) -> impl Iterator<Item = usize> + 'a {
+    group.iter().copied().filter(move |i| arr.is_valid(*i))
+}
+
+/// Reduce each group of row indexes of the array to a single value. Nulls are ignored by every
+/// aggregate, a group without any values produces a null except for `Count` which produces 0.
+/// NaN is the greatest float, as when sorting, and an integer sum which overflows is an error.
+pub fn aggregate(
+    arr: &ArrayRef,
+    agg: &Aggregate,
+    groups: &[Vec<usize>],
+) -> Result<ArrayRef, Error> {
+    match agg {
+        Aggregate::Count => count(arr, groups),
+        Aggregate::Min => min(arr, groups),
+        Aggregate::Max => max(arr, groups),
+        Aggregate::Sum => sum(arr, groups),
+        Aggregate::Avg => avg(arr, groups),
+    }
+}
+
+fn count(arr: &ArrayRef, groups: &[Vec<usize>]) -> Result<ArrayRef, Error> {
+    let values: Vec<u64> = groups
+        .iter()
+        .map(|group| valid(arr.as_ref(), group).count() as u64)
+        .collect();
+
+    Ok(Arc::new(UInt64Array::from(values)))
+}
+
+fn min(arr: &ArrayRef, groups: &[Vec<usize>]) -> Result<ArrayRef, Error> {
+    min_max!(
+        arr,
+        groups,
+        |value, current| total_cmp(&value, &current) == Ordering::Less,
+        [DataType::UInt8, UInt8Array],
+        [DataType::UInt16, UInt16Array],
+        [DataType::UInt32, UInt32Array],
+        [DataType::UInt64, UInt64Array],
+        [DataType::Int8, Int8Array],
+        [DataType::Int16, Int16Array],
+        [DataType::Int32, Int32Array],
+        [DataType::Int64, Int64Array],
+        [DataType::Float32, Float32Array],
+        [DataType::Float64, Float64Array]
+    )
+}
+
+fn max(arr: &ArrayRef, groups: &[Vec<usize>]) -> Result<ArrayRef, Error> {
+    min_max!(
+        arr,
+        groups,
+        |value, current| total_cmp(&value, &current) == Ordering::Greater,
+        [DataType::UInt8, UInt8Array],
+        [DataType::UInt16, UInt16Array],
+        [DataType::UInt32, UInt32Array],
+        [DataType::UInt64, UInt64Array],
+        [DataType::Int8, Int8Array],
+        [DataType::Int16, Int16Array],
+        [DataType::Int32, Int32Array],
+        [DataType::Int64, Int64Array],
+        [DataType::Float32, Float32Array],
+        [DataType::Float64, Float64Array]
+    )
+}
+
+fn sum(arr: &ArrayRef, groups: &[Vec<usize>]) -> Result<ArrayRef, Error> {
+    sum!(
+        arr,
+        groups,
+        [DataType::UInt8, UInt8Array, u64, UInt64Array],
+        [DataType::UInt16, UInt16Array, u64, UInt64Array],
+        [DataType::UInt32, UInt32Array, u64, UInt64Array],
+        [DataType::UInt64, UInt64Array, u64, UInt64Array],
+        [DataType::Int8, Int8Array, i64, Int64Array],
+        [DataType::Int16, Int16Array, i64, Int64Array],
+        [DataType::Int32, Int32Array, i64, Int64Array],
+        [DataType::Int64, Int64Array, i64, Int64Array],
+        [DataType::Float32, Float32Array, f64, Float64Array],
+        [DataType::Float64, Float64Array, f64, Float64Array]
+    )
+}
+
+fn avg(arr: &ArrayRef, groups: &[Vec<usize>]) -> Result<ArrayRef, Error> {
+    avg!(
+        arr,
+        groups,
+        [DataType::UInt8, UInt8Array],
+        [DataType::UInt16, UInt16Array],
+        [DataType::UInt32, UInt32Array],
+        [DataType::UInt64, UInt64Array],
+        [DataType::Int8, Int8Array],
+        [DataType::Int16, Int16Array],
+        [DataType::Int32, Int32Array],
+        [DataType::Int64, Int64Array],
+        [DataType::Float32, Float32Array],
+        [DataType::Float64, Float64Array]
+    )
+}
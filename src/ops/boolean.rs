@@ -0,0 +1,175 @@
+use crate::ops::cmp::Error;
+use arrow::array::{Array, BooleanArray};
+
+/// Kleene AND, false wins over null so `false AND null` is false while `true AND null` is null
+pub fn and(lhs: &BooleanArray, rhs: &BooleanArray) -> Result<BooleanArray, crate::Error> {
+    kleene(lhs, rhs, |l, r| match (l, r) {
+        (Some(false), _) | (_, Some(false)) => Some(false),
+        (Some(true), Some(true)) => Some(true),
+        _ => None,
+    })
+}
+
+/// Kleene OR, true wins over null so `true OR null` is true while `false OR null` is null
+pub fn or(lhs: &BooleanArray, rhs: &BooleanArray) -> Result<BooleanArray, crate::Error> {
+    kleene(lhs, rhs, |l, r| match (l, r) {
+        (Some(true), _) | (_, Some(true)) => Some(true),
+        (Some(false), Some(false)) => Some(false),
+        _ => None,
+    })
+}
+
+/// Negate each value, nulls stay null
+pub fn not(arr: &BooleanArray) -> Result<BooleanArray, crate::Error> {
+    let mut builder = BooleanArray::builder(arr.len());
+
+    for i in 0..arr.len() {
+        match value(arr, i) {
+            Some(v) => builder.append_value(!v)?,
+            None => builder.append_null()?,
+        }
+    }
+
+    Ok(builder.finish())
+}
+
+/// Replace the nulls with false, the rows which are kept by a filter must be known to be true
+pub fn null_as_false(arr: &BooleanArray) -> Result<BooleanArray, crate::Error> {
+    if arr.null_count() == 0 {
+        return Ok(BooleanArray::from(arr.data()));
+    }
+
+    let mut builder = BooleanArray::builder(arr.len());
+
+    for i in 0..arr.len() {
+        builder.append_value(value(arr, i).unwrap_or(false))?;
+    }
+
+    Ok(builder.finish())
+}
+
+fn value(arr: &BooleanArray, i: usize) -> Option<bool> {
+    if arr.is_null(i) {
+        None
+    } else {
+        Some(arr.value(i))
+    }
+}
+
+fn kleene<F>(lhs: &BooleanArray, rhs: &BooleanArray, f: F) -> Result<BooleanArray, crate::Error>
+where
+    F: Fn(Option<bool>, Option<bool>) -> Option<bool>,
+{
+    if lhs.len() != rhs.len() {
+        return Err(Error::UnequalLength {
+            lhs: lhs.len(),
+            rhs: rhs.len(),
+        }
+        .into());
+    }
+
+    let mut builder = BooleanArray::builder(lhs.len());
+
+    for i in 0..lhs.len() {
+        match f(value(lhs, i), value(rhs, i)) {
+            Some(v) => builder.append_value(v)?,
+            None => builder.append_null()?,
+        }
+    }
+
+    Ok(builder.finish())
+}
+
+#[cfg(test)]
+mod test_boolean {
+    use super::*;
+
+    fn values(arr: &BooleanArray) -> Vec<Option<bool>> {
+        (0..arr.len()).map(|i| value(arr, i)).collect()
+    }
+
+    fn sample() -> (BooleanArray, BooleanArray) {
+        let lhs = vec![
+            Some(true),
+            Some(true),
+            Some(true),
+            Some(false),
+            Some(false),
+            Some(false),
+            None,
+            None,
+            None,
+        ];
+        let rhs = vec![
+            Some(true),
+            Some(false),
+            None,
+            Some(true),
+            Some(false),
+            None,
+            Some(true),
+            Some(false),
+            None,
+        ];
+        (BooleanArray::from(lhs), BooleanArray::from(rhs))
+    }
+
+    #[test]
+    fn it_follows_kleene_logic() {
+        let (lhs, rhs) = sample();
+
+        assert_eq!(
+            values(&and(&lhs, &rhs).unwrap()),
+            vec![
+                Some(true),
+                Some(false),
+                None,
+                Some(false),
+                Some(false),
+                Some(false),
+                None,
+                Some(false),
+                None
+            ]
+        );
+
+        assert_eq!(
+            values(&or(&lhs, &rhs).unwrap()),
+            vec![
+                Some(true),
+                Some(true),
+                Some(true),
+                Some(true),
+                Some(false),
+                None,
+                Some(true),
+                None,
+                None
+            ]
+        );
+
+        assert_eq!(
+            values(&not(&rhs).unwrap()),
+            vec![
+                Some(false),
+                Some(true),
+                None,
+                Some(false),
+                Some(true),
+                None,
+                Some(false),
+                Some(true),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn it_treats_null_as_false() {
+        let arr = BooleanArray::from(vec![Some(true), None, Some(false)]);
+        assert_eq!(
+            values(&null_as_false(&arr).unwrap()),
+            vec![Some(true), Some(false), Some(false)]
+        );
+    }
+}
@@ -1,12 +1,13 @@
+use arrow::array::BinaryArray;
 use arrow::array::BooleanArray;
 use arrow::array::PrimitiveArray;
-use arrow::compute::kernels::comparison as cmp;
-use arrow::datatypes::ArrowNumericType;
+use arrow::datatypes::{self as dt, ArrowNumericType};
 use std::cmp::Ordering;
 use std::fmt::{Debug, Display};
 
+use crate::datatype::{is_lossy_promotion, promote_arrays, to_numbers};
 use crate::error;
-use crate::{Array, DataType};
+use crate::{as_array, Array, ArrayRef, DataType};
 
 use snafu::Snafu;
 
@@ -59,6 +60,39 @@ impl CmpOp {
     }
 }
 
+/// How NaN is ordered against the other floating point values
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NanOrdering {
+    /// NaN is equal to NaN and greater than every other value, including infinity
+    Largest,
+    /// NaN behaves as a null, comparisons with it are null and it sorts with the nulls
+    Null,
+}
+
+impl Default for NanOrdering {
+    fn default() -> NanOrdering {
+        NanOrdering::Largest
+    }
+}
+
+/// A total order over values which are only partially ordered, the unordered values (NaN) are
+/// equal to each other and greater than every ordered value
+pub fn total_cmp<T: PartialOrd>(lhs: &T, rhs: &T) -> Ordering {
+    match lhs.partial_cmp(rhs) {
+        Some(order) => order,
+        None => match (is_nan(lhs), is_nan(rhs)) {
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            _ => Ordering::Equal,
+        },
+    }
+}
+
+/// Only NaN is not comparable to itself
+pub fn is_nan<T: PartialOrd>(value: &T) -> bool {
+    value.partial_cmp(value).is_none()
+}
+
 impl Display for CmpOp {
     fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
         write!(fmt, "{}", self.as_str())
@@ -71,40 +105,104 @@ impl Debug for CmpOp {
     }
 }
 
+macro_rules! compare {
+    ($lhs:ident, $rhs:ident, $op:ident, $nans:ident, $( [$dt:path, $ty:ty] ),*) => {
+        match ($lhs.data_type(), $rhs.data_type(), $op) {
+            $( ($dt, $dt, op) => {
+                cmp_arrays(
+                    as_array!($lhs, PrimitiveArray<$ty>)?,
+                    as_array!($rhs, PrimitiveArray<$ty>)?,
+                    op,
+                    $nans
+                )
+            } ,)+
+            (DataType::Boolean, DataType::Boolean, op) => cmp_bool_arrays(
+                as_array!($lhs, BooleanArray)?,
+                as_array!($rhs, BooleanArray)?,
+                op
+            ),
+            (DataType::Utf8, DataType::Utf8, op) => cmp_utf8_arrays(
+                as_array!($lhs, BinaryArray)?,
+                as_array!($rhs, BinaryArray)?,
+                op
+            ),
+            (lhs_dt, rhs_dt, op) => Err(Error::InvalidComparison {
+                lhs: lhs_dt.clone(),
+                rhs: rhs_dt.clone(),
+                op,
+            }
+            .into()),
+        }
+    }
+}
+
+/// Compare two arrays element by element, numeric arrays of different data types are first
+/// promoted to a common data type and strings are compared lexicographically
+pub fn compare(
+    lhs: &ArrayRef,
+    rhs: &ArrayRef,
+    op: CmpOp,
+    nans: NanOrdering,
+) -> Result<BooleanArray, error::Error> {
+    // a common float type would round the 64 bit integers, so they are compared exactly
+    if is_lossy_promotion(lhs.data_type(), rhs.data_type()) {
+        let lhs_values = to_numbers(lhs)?;
+        let rhs_values = to_numbers(rhs)?;
+
+        return cmp_rows(lhs, rhs, |i| {
+            cmp_value(&lhs_values[i], &rhs_values[i], &op, nans)
+        });
+    }
+
+    let (lhs, rhs) = promote_arrays(lhs, rhs)?;
+
+    compare!(
+        lhs,
+        rhs,
+        op,
+        nans,
+        [DataType::UInt8, dt::UInt8Type],
+        [DataType::UInt16, dt::UInt16Type],
+        [DataType::UInt32, dt::UInt32Type],
+        [DataType::UInt64, dt::UInt64Type],
+        [DataType::Int8, dt::Int8Type],
+        [DataType::Int16, dt::Int16Type],
+        [DataType::Int32, dt::Int32Type],
+        [DataType::Int64, dt::Int64Type],
+        [DataType::Float32, dt::Float32Type],
+        [DataType::Float64, dt::Float64Type]
+    )
+}
+
+/// Compare two primitive arrays, a null on either side produces a null. NaN is ordered by `nans`.
 pub fn cmp_arrays<T: ArrowNumericType>(
     lhs: &PrimitiveArray<T>,
     rhs: &PrimitiveArray<T>,
     op: CmpOp,
+    nans: NanOrdering,
 ) -> Result<BooleanArray, error::Error> {
-    (match op {
-        CmpOp::NotEq => cmp::neq(lhs, rhs),
-        CmpOp::Eq => cmp::eq(lhs, rhs),
-        CmpOp::Lt => cmp::lt(lhs, rhs),
-        CmpOp::LtEq => cmp::lt_eq(lhs, rhs),
-        CmpOp::Gt => cmp::gt(lhs, rhs),
-        CmpOp::GtEq => cmp::gt_eq(lhs, rhs),
-    })
-    .map_err(|source| error::Error::Arrow {
-        source: source.into(),
+    cmp_rows(lhs, rhs, |i| {
+        cmp_value(&lhs.value(i), &rhs.value(i), &op, nans)
     })
 }
 
+/// Compare two valid values, `None` when either is NaN and NaN is treated as null
+pub fn cmp_value<T: PartialOrd>(lhs: &T, rhs: &T, op: &CmpOp, nans: NanOrdering) -> Option<bool> {
+    if nans == NanOrdering::Null && (is_nan(lhs) || is_nan(rhs)) {
+        return None;
+    }
+
+    Some(op.eq_ord(total_cmp(lhs, rhs)))
+}
+
 pub fn cmp_bool_arrays(
     lhs: &BooleanArray,
     rhs: &BooleanArray,
     op: CmpOp,
 ) -> Result<BooleanArray, error::Error> {
-    if lhs.len() != rhs.len() {
-        return Err(Error::UnequalLength {
-            lhs: lhs.len(),
-            rhs: rhs.len(),
-        }
-        .into());
-    }
-
     match op {
-        CmpOp::Eq => Ok(cmp_bool(lhs, rhs, |a, b| a == b)),
-        CmpOp::NotEq => Ok(cmp_bool(lhs, rhs, |a, b| a != b)),
+        CmpOp::Eq => cmp_rows(lhs, rhs, |i| Some(lhs.value(i) == rhs.value(i))),
+        CmpOp::NotEq => cmp_rows(lhs, rhs, |i| Some(lhs.value(i) != rhs.value(i))),
         _ => Err(Error::InvalidComparison {
             lhs: lhs.data_type().clone(),
             rhs: rhs.data_type().clone(),
@@ -114,16 +212,128 @@ pub fn cmp_bool_arrays(
     }
 }
 
-fn cmp_bool<F>(lhs: &BooleanArray, rhs: &BooleanArray, f: F) -> BooleanArray
+/// Compare two Utf8 arrays, the byte order of UTF-8 matches the order of the code points
+pub fn cmp_utf8_arrays(
+    lhs: &BinaryArray,
+    rhs: &BinaryArray,
+    op: CmpOp,
+) -> Result<BooleanArray, error::Error> {
+    cmp_rows(lhs, rhs, |i| {
+        Some(op.eq_ord(lhs.value(i).cmp(rhs.value(i))))
+    })
+}
+
+/// Compare each row of the arrays, following SQL the result is null when either value is null
+fn cmp_rows<F>(lhs: &dyn Array, rhs: &dyn Array, f: F) -> Result<BooleanArray, error::Error>
 where
-    F: Fn(bool, bool) -> bool,
+    F: Fn(usize) -> Option<bool>,
 {
+    if lhs.len() != rhs.len() {
+        return Err(Error::UnequalLength {
+            lhs: lhs.len(),
+            rhs: rhs.len(),
+        }
+        .into());
+    }
+
     let mut out = BooleanArray::builder(lhs.len());
 
     for i in 0..lhs.len() {
-        out.append_value(f(lhs.value(i), rhs.value(i)))
-            .expect("should append value");
+        if lhs.is_null(i) || rhs.is_null(i) {
+            out.append_null()?;
+            continue;
+        }
+
+        match f(i) {
+            Some(value) => out.append_value(value)?,
+            None => out.append_null()?,
+        }
+    }
+
+    Ok(out.finish())
+}
+
+#[cfg(test)]
+mod test_cmp {
+    use super::*;
+    use arrow::array::{Float64Array, Int32Array, Int64Array, UInt64Array, UInt8Array};
+    use std::sync::Arc;
+
+    fn values(arr: &BooleanArray) -> Vec<Option<bool>> {
+        (0..arr.len())
+            .map(|i| {
+                if arr.is_null(i) {
+                    None
+                } else {
+                    Some(arr.value(i))
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn it_compares_nulls_as_null() {
+        let lhs: ArrayRef = Arc::new(Int32Array::from(vec![Some(1), None, Some(3), None]));
+        let rhs: ArrayRef = Arc::new(UInt8Array::from(vec![Some(1), Some(2), None, None]));
+
+        let res = compare(&lhs, &rhs, CmpOp::Eq, NanOrdering::Largest).unwrap();
+        assert_eq!(values(&res), vec![Some(true), None, None, None]);
+
+        let lhs: ArrayRef = Arc::new(BooleanArray::from(vec![Some(true), None]));
+        let rhs: ArrayRef = Arc::new(BooleanArray::from(vec![Some(false), Some(false)]));
+
+        let res = compare(&lhs, &rhs, CmpOp::NotEq, NanOrdering::Largest).unwrap();
+        assert_eq!(values(&res), vec![Some(true), None]);
+    }
+
+    #[test]
+    fn it_orders_nan() {
+        let lhs: ArrayRef = Arc::new(Float64Array::from(vec![std::f64::NAN, std::f64::NAN, 1.0]));
+        let rhs: ArrayRef = Arc::new(Float64Array::from(vec![
+            std::f64::NAN,
+            std::f64::INFINITY,
+            std::f64::NAN,
+        ]));
+
+        let res = compare(&lhs, &rhs, CmpOp::GtEq, NanOrdering::Largest).unwrap();
+        assert_eq!(values(&res), vec![Some(true), Some(true), Some(false)]);
+
+        let res = compare(&lhs, &rhs, CmpOp::GtEq, NanOrdering::Null).unwrap();
+        assert_eq!(values(&res), vec![None, None, None]);
+
+        assert_eq!(total_cmp(&-0.0, &0.0), Ordering::Equal);
     }
 
-    out.finish()
+    #[test]
+    fn it_compares_64_bit_integers_exactly() {
+        let lhs: ArrayRef = Arc::new(UInt64Array::from(vec![
+            9_007_199_254_740_993,
+            std::u64::MAX,
+            3,
+        ]));
+        let rhs: ArrayRef = Arc::new(Int64Array::from(vec![9_007_199_254_740_992, -1, 3]));
+
+        let res = compare(&lhs, &rhs, CmpOp::Eq, NanOrdering::Largest).unwrap();
+        assert_eq!(values(&res), vec![Some(false), Some(false), Some(true)]);
+
+        let res = compare(&lhs, &rhs, CmpOp::Gt, NanOrdering::Largest).unwrap();
+        assert_eq!(values(&res), vec![Some(true), Some(true), Some(false)]);
+
+        let lhs: ArrayRef = Arc::new(Int64Array::from(vec![
+            9_007_199_254_740_993,
+            -3,
+            std::i64::MAX,
+        ]));
+        let rhs: ArrayRef = Arc::new(Float64Array::from(vec![
+            9_007_199_254_740_992.0,
+            -2.5,
+            std::f64::NAN,
+        ]));
+
+        let res = compare(&lhs, &rhs, CmpOp::Gt, NanOrdering::Largest).unwrap();
+        assert_eq!(values(&res), vec![Some(true), Some(false), Some(false)]);
+
+        let res = compare(&lhs, &rhs, CmpOp::Lt, NanOrdering::Null).unwrap();
+        assert_eq!(values(&res), vec![Some(false), Some(true), None]);
+    }
 }
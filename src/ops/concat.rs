@@ -0,0 +1,217 @@
+use crate::{as_array, Error};
+use arrow::array::{
+    Array, ArrayRef, BinaryArray, BinaryBuilder, BooleanArray, PrimitiveArray, PrimitiveBuilder,
+};
+use arrow::datatypes::{self as dt, ArrowNumericType, DataType, DateUnit, IntervalUnit, TimeUnit};
+use std::sync::Arc;
+
+macro_rules! concat {
+    ($arrays:ident, $data_type:ident, $( [$dt:pat, $ty:ty] ),*) => {
+        match $data_type {
+            $($dt => concat_primitive::<$ty>($arrays),)+
+            DataType::Boolean => concat_boolean($arrays),
+            DataType::Utf8 => concat_utf8($arrays),
+            data_type => Err(Error::UnknownDataType {
+                data_type: data_type.clone(),
+            }),
+        }
+    };
+}
+
+/// Join the arrays end to end into a single array, every array must have the same data type
+pub fn concat(arrays: &[ArrayRef]) -> Result<ArrayRef, Error> {
+    match arrays {
+        [] => {
+            return Err(Error::InvalidOperation {
+                op: "concat".to_string(),
+                lhs: DataType::Null,
+                rhs: DataType::Null,
+            })
+        }
+        [arr] => return Ok(arr.clone()),
+        _ => (),
+    }
+
+    concat_with_type(arrays[0].data_type(), arrays)
+}
+
+/// Join the arrays into a single array of the data type, no arrays produces an empty array
+pub fn concat_with_type(data_type: &DataType, arrays: &[ArrayRef]) -> Result<ArrayRef, Error> {
+    if arrays.iter().any(|arr| arr.data_type() != data_type) {
+        return Err(Error::WrongType);
+    }
+
+    concat!(
+        arrays,
+        data_type,
+        [DataType::UInt8, dt::UInt8Type],
+        [DataType::UInt16, dt::UInt16Type],
+        [DataType::UInt32, dt::UInt32Type],
+        [DataType::UInt64, dt::UInt64Type],
+        [DataType::Int8, dt::Int8Type],
+        [DataType::Int16, dt::Int16Type],
+        [DataType::Int32, dt::Int32Type],
+        [DataType::Int64, dt::Int64Type],
+        [DataType::Float32, dt::Float32Type],
+        [DataType::Float64, dt::Float64Type],
+        [DataType::Date32(DateUnit::Day), dt::Date32Type],
+        [DataType::Date64(DateUnit::Millisecond), dt::Date64Type],
+        [DataType::Time32(TimeUnit::Second), dt::Time32SecondType],
+        [
+            DataType::Time32(TimeUnit::Millisecond),
+            dt::Time32MillisecondType
+        ],
+        [
+            DataType::Time64(TimeUnit::Microsecond),
+            dt::Time64MicrosecondType
+        ],
+        [
+            DataType::Time64(TimeUnit::Nanosecond),
+            dt::Time64NanosecondType
+        ],
+        [
+            DataType::Timestamp(TimeUnit::Second),
+            dt::TimestampSecondType
+        ],
+        [
+            DataType::Timestamp(TimeUnit::Millisecond),
+            dt::TimestampMillisecondType
+        ],
+        [
+            DataType::Timestamp(TimeUnit::Microsecond),
+            dt::TimestampMicrosecondType
+        ],
+        [
+            DataType::Timestamp(TimeUnit::Nanosecond),
+            dt::TimestampNanosecondType
+        ],
+        [
+            DataType::Interval(IntervalUnit::YearMonth),
+            dt::IntervalYearMonthType
+        ],
+        [
+            DataType::Interval(IntervalUnit::DayTime),
+            dt::IntervalDayTimeType
+        ]
+    )
+}
+
+fn total_len(arrays: &[ArrayRef]) -> usize {
+    arrays.iter().map(|arr| arr.len()).sum()
+}
+
+fn concat_primitive<T: ArrowNumericType>(arrays: &[ArrayRef]) -> Result<ArrayRef, Error> {
+    let mut builder = PrimitiveBuilder::<T>::new(total_len(arrays));
+
+    for arr in arrays {
+        let arr = as_array!(arr, PrimitiveArray<T>)?;
+
+        for i in 0..arr.len() {
+            if arr.is_valid(i) {
+                builder.append_value(arr.value(i))?;
+            } else {
+                builder.append_null()?;
+            }
+        }
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+fn concat_boolean(arrays: &[ArrayRef]) -> Result<ArrayRef, Error> {
+    let mut builder = BooleanArray::builder(total_len(arrays));
+
+    for arr in arrays {
+        let arr = as_array!(arr, BooleanArray)?;
+
+        for i in 0..arr.len() {
+            if arr.is_valid(i) {
+                builder.append_value(arr.value(i))?;
+            } else {
+                builder.append_null()?;
+            }
+        }
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+fn concat_utf8(arrays: &[ArrayRef]) -> Result<ArrayRef, Error> {
+    let mut builder = BinaryBuilder::new(total_len(arrays));
+
+    for arr in arrays {
+        let arr = as_array!(arr, BinaryArray)?;
+
+        for i in 0..arr.len() {
+            if arr.is_valid(i) {
+                builder.append_value(arr.value(i))?;
+            } else {
+                builder.append_null()?;
+            }
+        }
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+#[cfg(test)]
+mod test_concat {
+    use super::*;
+    use arrow::array::{Date32Array, Int32Array};
+
+    #[test]
+    fn it_concats_arrays() {
+        let arrays: Vec<ArrayRef> = vec![
+            Arc::new(Int32Array::from(vec![Some(1), None])),
+            Arc::new(Int32Array::from(vec![Some(3)])),
+        ];
+
+        let arr = concat(&arrays).unwrap();
+        assert_eq!(
+            as_array!(arr, Int32Array).unwrap(),
+            &Int32Array::from(vec![Some(1), None, Some(3)])
+        );
+
+        let mut builder = BinaryBuilder::new(2);
+        builder.append_value(b"a").unwrap();
+        builder.append_null().unwrap();
+        let strings: ArrayRef = Arc::new(builder.finish());
+
+        let arr = concat(&[strings.clone(), strings]).unwrap();
+        let arr = as_array!(arr, BinaryArray).unwrap();
+        assert_eq!(arr.len(), 4);
+        assert_eq!(arr.value(2), b"a");
+        assert!(arr.is_null(3));
+    }
+
+    #[test]
+    fn it_concats_temporal_arrays() {
+        let arrays: Vec<ArrayRef> = vec![
+            Arc::new(Date32Array::from(vec![Some(1), None])),
+            Arc::new(Date32Array::from(vec![Some(3)])),
+        ];
+
+        let arr = concat(&arrays).unwrap();
+        assert_eq!(
+            as_array!(arr, Date32Array).unwrap(),
+            &Date32Array::from(vec![Some(1), None, Some(3)])
+        );
+    }
+
+    #[test]
+    fn it_rejects_mixed_types() {
+        let arrays: Vec<ArrayRef> = vec![
+            Arc::new(Int32Array::from(vec![1])),
+            Arc::new(BooleanArray::from(vec![true])),
+        ];
+
+        assert!(concat(&arrays).is_err());
+    }
+
+    #[test]
+    fn it_concats_no_arrays_with_a_type() {
+        let arr = concat_with_type(&DataType::Utf8, &[]).unwrap();
+        assert_eq!(arr.data_type(), &DataType::Utf8);
+        assert_eq!(arr.len(), 0);
+    }
+}
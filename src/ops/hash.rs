@@ -0,0 +1,113 @@
+use crate::{as_array, Error};
+use arrow::array::{
+    Array, ArrayRef, BinaryArray, BooleanArray, Float32Array, Float64Array, Int16Array, Int32Array,
+    Int64Array, Int8Array, UInt16Array, UInt32Array, UInt64Array, UInt8Array,
+};
+use arrow::datatypes::DataType;
+
+macro_rules! encode_column {
+    ($arr:ident, $keys:ident, $( [$dt:path, $ty:ty] ),*) => {
+        match $arr.data_type() {
+            $($dt => {
+                encode(as_array!($arr, $ty)?, $keys, |arr, i, key| {
+                    key.extend_from_slice(&arr.value(i).to_le_bytes())
+                });
+                Ok(())
+            },)+
+            DataType::Float32 => {
+                encode(as_array!($arr, Float32Array)?, $keys, |arr, i, key| {
+                    key.extend_from_slice(&canonical_f32(arr.value(i)).to_bits().to_le_bytes())
+                });
+                Ok(())
+            }
+            DataType::Float64 => {
+                encode(as_array!($arr, Float64Array)?, $keys, |arr, i, key| {
+                    key.extend_from_slice(&canonical_f64(arr.value(i)).to_bits().to_le_bytes())
+                });
+                Ok(())
+            }
+            DataType::Boolean => {
+                encode(as_array!($arr, BooleanArray)?, $keys, |arr, i, key| {
+                    key.push(arr.value(i) as u8)
+                });
+                Ok(())
+            }
+            DataType::Utf8 => {
+                encode(as_array!($arr, BinaryArray)?, $keys, |arr, i, key| {
+                    let value = arr.value(i);
+                    key.extend_from_slice(&(value.len() as u64).to_le_bytes());
+                    key.extend_from_slice(value);
+                });
+                Ok(())
+            }
+            data_type => Err(Error::UnknownDataType {
+                data_type: data_type.clone(),
+            }),
+        }
+    };
+}
+
+/// Encode each row of the columns into a byte key. Two rows produce the same key only when every
+/// one of their values is equal, nulls are considered equal to each other.
+pub fn row_keys(columns: &[ArrayRef]) -> Result<Vec<Vec<u8>>, Error> {
+    let len = columns.first().map(|arr| arr.len()).unwrap_or(0);
+    let mut keys: Vec<Vec<u8>> = vec![vec![]; len];
+
+    for arr in columns {
+        encode_column(arr, &mut keys)?;
+    }
+
+    Ok(keys)
+}
+
+fn encode_column(arr: &ArrayRef, keys: &mut [Vec<u8>]) -> Result<(), Error> {
+    encode_column!(
+        arr,
+        keys,
+        [DataType::UInt8, UInt8Array],
+        [DataType::UInt16, UInt16Array],
+        [DataType::UInt32, UInt32Array],
+        [DataType::UInt64, UInt64Array],
+        [DataType::Int8, Int8Array],
+        [DataType::Int16, Int16Array],
+        [DataType::Int32, Int32Array],
+        [DataType::Int64, Int64Array]
+    )
+}
+
+/// Prefix each value with a tag so that a null can never collide with a value
+fn encode<A, F>(arr: &A, keys: &mut [Vec<u8>], f: F)
+where
+    A: Array,
+    F: Fn(&A, usize, &mut Vec<u8>),
+{
+    for (i, key) in keys.iter_mut().enumerate() {
+        if arr.is_null(i) {
+            key.push(0);
+        } else {
+            key.push(1);
+            f(arr, i, key);
+        }
+    }
+}
+
+/// -0.0 and 0.0 compare equal and so do all of the NaN representations
+fn canonical_f32(value: f32) -> f32 {
+    if value == 0.0 {
+        0.0
+    } else if value.is_nan() {
+        std::f32::NAN
+    } else {
+        value
+    }
+}
+
+fn canonical_f64(value: f64) -> f64 {
+    if value == 0.0 {
+        0.0
+    } else if value.is_nan() {
+        std::f64::NAN
+    } else {
+        value
+    }
+}
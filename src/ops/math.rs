@@ -1,10 +1,14 @@
-use crate::Error;
+use crate::datatype::promote_arrays;
+use crate::error::arrow_error::ArrowError;
+use crate::query::select::{Arithmetic, Unary};
+use crate::{as_array, Error};
 use arrow::array::{
     Array, ArrayRef, Float32Array, Float64Array, Int16Array, Int32Array, Int64Array, Int8Array,
-    PrimitiveArray, UInt16Array, UInt32Array, UInt64Array, UInt8Array,
+    PrimitiveArray, PrimitiveBuilder, UInt16Array, UInt32Array, UInt64Array, UInt8Array,
 };
 use arrow::compute;
-use arrow::datatypes::{ArrowNumericType, DataType};
+use arrow::datatypes::{self as dt, ArrowNumericType, DataType};
+use std::convert::TryFrom;
 use std::sync::Arc;
 
 macro_rules! array_op {
@@ -43,6 +47,252 @@ macro_rules! do_op {
     }};
 }
 
+/// What to produce when dividing by zero with `Div`, `IntDiv` or `Mod`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DivideByZero {
+    /// Fail with `ArrowError::DivideByZero`
+    Error,
+    /// Produce a null for the row
+    Null,
+}
+
+impl Default for DivideByZero {
+    fn default() -> DivideByZero {
+        DivideByZero::Error
+    }
+}
+
+macro_rules! arithmetic {
+    (
+        $lhs:ident,
+        $rhs:ident,
+        $op:ident,
+        $on_zero:ident,
+        [$([$idt:path, $ity:ty]),*],
+        [$([$fdt:path, $fty:ty]),*]
+    ) => {
+        match ($lhs.data_type(), $rhs.data_type()) {
+            $(
+                ($idt, $idt) => {
+                    let lhs_arr = as_array!($lhs, PrimitiveArray<$ity>)?;
+                    let rhs_arr = as_array!($rhs, PrimitiveArray<$ity>)?;
+
+                    match $op {
+                        Arithmetic::Add => add(lhs_arr, rhs_arr),
+                        Arithmetic::Sub => subtract(lhs_arr, rhs_arr),
+                        Arithmetic::Mul => multiply(lhs_arr, rhs_arr),
+                        Arithmetic::Div | Arithmetic::IntDiv => binary(lhs_arr, rhs_arr, |l, r| {
+                            // only the minimum divided by -1 overflows
+                            checked(r == 0, $on_zero, || l.checked_div(r))?
+                                .map(|value| or_overflow(value, $op, $lhs.data_type()))
+                                .transpose()
+                        }),
+                        Arithmetic::Mod => binary(lhs_arr, rhs_arr, |l, r| {
+                            checked(r == 0, $on_zero, || l.wrapping_rem(r))
+                        }),
+                        Arithmetic::Pow => binary(lhs_arr, rhs_arr, |l, r| {
+                            match u32::try_from(r) {
+                                Ok(exp) => {
+                                    or_overflow(l.checked_pow(exp), $op, $lhs.data_type()).map(Some)
+                                }
+                                Err(_) => Err(Error::InvalidOperation {
+                                    op: $op.as_str().to_string(),
+                                    lhs: $lhs.data_type().clone(),
+                                    rhs: $rhs.data_type().clone(),
+                                }),
+                            }
+                        }),
+                    }
+                },
+            )+
+            $(
+                ($fdt, $fdt) => {
+                    let lhs_arr = as_array!($lhs, PrimitiveArray<$fty>)?;
+                    let rhs_arr = as_array!($rhs, PrimitiveArray<$fty>)?;
+
+                    match $op {
+                        Arithmetic::Add => add(lhs_arr, rhs_arr),
+                        Arithmetic::Sub => subtract(lhs_arr, rhs_arr),
+                        Arithmetic::Mul => multiply(lhs_arr, rhs_arr),
+                        Arithmetic::Div => binary(lhs_arr, rhs_arr, |l, r| {
+                            checked(r == 0.0, $on_zero, || l / r)
+                        }),
+                        Arithmetic::IntDiv => binary(lhs_arr, rhs_arr, |l, r| {
+                            checked(r == 0.0, $on_zero, || (l / r).trunc())
+                        }),
+                        Arithmetic::Mod => binary(lhs_arr, rhs_arr, |l, r| {
+                            checked(r == 0.0, $on_zero, || l % r)
+                        }),
+                        Arithmetic::Pow => binary(lhs_arr, rhs_arr, |l, r| Ok(Some(l.powf(r)))),
+                    }
+                },
+            )+
+            (lhs_dt, rhs_dt) => Err(Error::InvalidOperation {
+                op: $op.as_str().to_string(),
+                lhs: lhs_dt.clone(),
+                rhs: rhs_dt.clone(),
+            }),
+        }
+    }
+}
+
+/// Apply the arithmetic operation to two numeric arrays, arrays of different data types are first
+/// promoted to a common data type. Integer division truncates toward zero and an integer `Pow`
+/// requires a non-negative exponent, a `Div` or `Pow` which overflows fails with `Overflow`.
+pub fn arithmetic(
+    lhs: &ArrayRef,
+    rhs: &ArrayRef,
+    op: &Arithmetic,
+    on_zero: DivideByZero,
+) -> Result<ArrayRef, Error> {
+    let (lhs, rhs) = promote_arrays(lhs, rhs)?;
+
+    arithmetic!(
+        lhs,
+        rhs,
+        op,
+        on_zero,
+        [
+            [DataType::UInt8, dt::UInt8Type],
+            [DataType::UInt16, dt::UInt16Type],
+            [DataType::UInt32, dt::UInt32Type],
+            [DataType::UInt64, dt::UInt64Type],
+            [DataType::Int8, dt::Int8Type],
+            [DataType::Int16, dt::Int16Type],
+            [DataType::Int32, dt::Int32Type],
+            [DataType::Int64, dt::Int64Type]
+        ],
+        [
+            [DataType::Float32, dt::Float32Type],
+            [DataType::Float64, dt::Float64Type]
+        ]
+    )
+}
+
+macro_rules! unary {
+    (
+        $arr:ident,
+        $op:ident,
+        [$([$sdt:path, $sty:ty]),*],
+        [$([$udt:path, $uty:ty]),*],
+        [$([$fdt:path, $fty:ty]),*]
+    ) => {
+        match ($arr.data_type(), $op) {
+            $(
+                ($sdt, Unary::Neg) => unary_op(as_array!($arr, PrimitiveArray<$sty>)?, |v| v.wrapping_neg()),
+                ($sdt, Unary::Abs) => unary_op(as_array!($arr, PrimitiveArray<$sty>)?, |v| v.wrapping_abs()),
+            )+
+            $(
+                ($udt, Unary::Abs) => unary_op(as_array!($arr, PrimitiveArray<$uty>)?, |v| v),
+            )+
+            $(
+                ($fdt, Unary::Neg) => unary_op(as_array!($arr, PrimitiveArray<$fty>)?, |v| -v),
+                ($fdt, Unary::Abs) => unary_op(as_array!($arr, PrimitiveArray<$fty>)?, |v| v.abs()),
+            )+
+            (data_type, op) => Err(Error::InvalidOperation {
+                op: op.as_str().to_string(),
+                lhs: data_type.clone(),
+                rhs: data_type.clone(),
+            }),
+        }
+    }
+}
+
+/// Apply the unary operation to a numeric array, unsigned integers can't be negated
+pub fn unary(arr: &ArrayRef, op: &Unary) -> Result<ArrayRef, Error> {
+    unary!(
+        arr,
+        op,
+        [
+            [DataType::Int8, dt::Int8Type],
+            [DataType::Int16, dt::Int16Type],
+            [DataType::Int32, dt::Int32Type],
+            [DataType::Int64, dt::Int64Type]
+        ],
+        [
+            [DataType::UInt8, dt::UInt8Type],
+            [DataType::UInt16, dt::UInt16Type],
+            [DataType::UInt32, dt::UInt32Type],
+            [DataType::UInt64, dt::UInt64Type]
+        ],
+        [
+            [DataType::Float32, dt::Float32Type],
+            [DataType::Float64, dt::Float64Type]
+        ]
+    )
+}
+
+/// Produce the value unless the divisor is zero, in which case `on_zero` decides the outcome
+fn checked<N, F>(is_zero: bool, on_zero: DivideByZero, f: F) -> Result<Option<N>, Error>
+where
+    F: FnOnce() -> N,
+{
+    match (is_zero, on_zero) {
+        (false, _) => Ok(Some(f())),
+        (true, DivideByZero::Null) => Ok(None),
+        (true, DivideByZero::Error) => Err(Error::Arrow {
+            source: ArrowError::DivideByZero,
+        }),
+    }
+}
+
+/// The value of a checked operation, `None` means it overflowed the data type
+fn or_overflow<N>(value: Option<N>, op: &Arithmetic, data_type: &DataType) -> Result<N, Error> {
+    value.ok_or_else(|| Error::Overflow {
+        op: op.as_str().to_string(),
+        data_type: data_type.clone(),
+    })
+}
+
+/// Apply the function to each pair of values, a null on either side produces a null
+fn binary<T, F>(lhs: &PrimitiveArray<T>, rhs: &PrimitiveArray<T>, f: F) -> Result<ArrayRef, Error>
+where
+    T: ArrowNumericType,
+    F: Fn(T::Native, T::Native) -> Result<Option<T::Native>, Error>,
+{
+    if lhs.len() != rhs.len() {
+        return Err(Error::Arrow {
+            source: ArrowError::ComputeError {
+                inner: "Cannot perform math operation on arrays of different length".to_string(),
+            },
+        });
+    }
+
+    let mut builder = PrimitiveBuilder::<T>::new(lhs.len());
+
+    for i in 0..lhs.len() {
+        if lhs.is_null(i) || rhs.is_null(i) {
+            builder.append_null()?;
+            continue;
+        }
+
+        match f(lhs.value(i), rhs.value(i))? {
+            Some(value) => builder.append_value(value)?,
+            None => builder.append_null()?,
+        }
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+fn unary_op<T, F>(arr: &PrimitiveArray<T>, f: F) -> Result<ArrayRef, Error>
+where
+    T: ArrowNumericType,
+    F: Fn(T::Native) -> T::Native,
+{
+    let mut builder = PrimitiveBuilder::<T>::new(arr.len());
+
+    for i in 0..arr.len() {
+        if arr.is_null(i) {
+            builder.append_null()?;
+        } else {
+            builder.append_value(f(arr.value(i)))?;
+        }
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
 pub fn add<T: ArrowNumericType>(
     lhs: &PrimitiveArray<T>,
     rhs: &PrimitiveArray<T>,
@@ -70,12 +320,3 @@ pub fn multiply<T: ArrowNumericType>(
 ) -> Result<ArrayRef, Error> {
     array_op!(lhs, rhs, multiply, "multiply")
 }
-
-// pub fn modulo<T: ArrowNumericType>(
-//     lhs: PrimitiveArray<T>,
-//     rhs: PrimitiveArray<T>,
-// ) -> Result<ArrayRef, Error> {
-//     compute::math_op(&lhs, &rhs, |l, r| Ok(l % r))
-//         .map(|arr| Arc::new(arr) as ArrayRef)
-//         .map_err(|err| err.into())
-// }
@@ -1,15 +1,37 @@
-use crate::error::Error;
-use arrow::array::PrimitiveArray;
-use arrow::datatypes::ArrowNumericType;
+use crate::column::Column;
+use crate::ops::cmp::{is_nan, total_cmp, NanOrdering};
+use crate::ops::take::take_rows;
+use crate::{as_array, View};
+use arrow::array::{Array, ArrayRef, BinaryArray, BooleanArray, PrimitiveArray};
+use arrow::datatypes::{self as dt, DataType};
+use snafu::Snafu;
+use std::cmp::Ordering;
 use std::str::FromStr;
 
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("The column does not exist {}", column))]
+    InvalidColumn { column: Column },
+
+    #[snafu(display("Columns of the data type {:?} cannot be sorted", data_type))]
+    InvalidSortDataType { data_type: DataType },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Sort {
     ASC,
     DESC,
 }
 
+/// Placement of the null values within a sorted column
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Nulls {
+    First,
+    Last,
+}
+
 impl FromStr for Sort {
-    type Err = Error;
+    type Err = crate::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         if unicase::eq(s, "asc") {
@@ -20,7 +42,7 @@ impl FromStr for Sort {
             return Ok(Sort::DESC);
         }
 
-        Err(Error::FromStrError {
+        Err(crate::Error::FromStrError {
             value: s.to_string(),
             description: Some("Allowed options are asc or desc"),
         })
@@ -28,5 +50,241 @@ impl FromStr for Sort {
 }
 
 impl Sort {
-    pub fn sort<T: ArrowNumericType>(arr: PrimitiveArray<T>) {}
+    /// Nulls are ordered as if they were larger than any value, so they come last when sorting
+    /// ascending and first when sorting descending
+    pub fn default_nulls(self) -> Nulls {
+        match self {
+            Sort::ASC => Nulls::Last,
+            Sort::DESC => Nulls::First,
+        }
+    }
+}
+
+type Comparator<'a> = Box<dyn Fn(usize, usize) -> Ordering + 'a>;
+type IsMissing<'a> = Box<dyn Fn(usize) -> bool + 'a>;
+
+macro_rules! comparator {
+    ($arr:ident, $( [$dt:path, $ty:ty] ),*) => {
+        match $arr.data_type() {
+            $($dt => {
+                let arr = as_array!($arr, PrimitiveArray<$ty>)?;
+                Ok(Box::new(move |i: usize, j: usize| total_cmp(&arr.value(i), &arr.value(j))) as Comparator)
+            },)+
+            DataType::Boolean => {
+                let arr = as_array!($arr, BooleanArray)?;
+                Ok(Box::new(move |i: usize, j: usize| arr.value(i).cmp(&arr.value(j))) as Comparator)
+            }
+            DataType::Utf8 => {
+                let arr = as_array!($arr, BinaryArray)?;
+                Ok(Box::new(move |i: usize, j: usize| arr.value(i).cmp(arr.value(j))) as Comparator)
+            }
+            data_type => Err(Error::InvalidSortDataType {
+                data_type: data_type.clone(),
+            }
+            .into()),
+        }
+    };
+}
+
+/// Compare the valid values at two indexes of the array
+fn comparator(arr: &ArrayRef) -> Result<Comparator<'_>, crate::Error> {
+    comparator!(
+        arr,
+        [DataType::UInt8, dt::UInt8Type],
+        [DataType::UInt16, dt::UInt16Type],
+        [DataType::UInt32, dt::UInt32Type],
+        [DataType::UInt64, dt::UInt64Type],
+        [DataType::Int8, dt::Int8Type],
+        [DataType::Int16, dt::Int16Type],
+        [DataType::Int32, dt::Int32Type],
+        [DataType::Int64, dt::Int64Type],
+        [DataType::Float32, dt::Float32Type],
+        [DataType::Float64, dt::Float64Type]
+    )
+}
+
+/// Whether the row is placed with the nulls, NaN joins them when it is treated as null
+fn is_missing(arr: &ArrayRef, nans: NanOrdering) -> Result<IsMissing<'_>, crate::Error> {
+    match (arr.data_type(), nans) {
+        (DataType::Float32, NanOrdering::Null) => {
+            let arr = as_array!(arr, PrimitiveArray<dt::Float32Type>)?;
+            Ok(Box::new(move |i: usize| {
+                arr.is_null(i) || is_nan(&arr.value(i))
+            }))
+        }
+        (DataType::Float64, NanOrdering::Null) => {
+            let arr = as_array!(arr, PrimitiveArray<dt::Float64Type>)?;
+            Ok(Box::new(move |i: usize| {
+                arr.is_null(i) || is_nan(&arr.value(i))
+            }))
+        }
+        _ => Ok(Box::new(move |i: usize| arr.is_null(i))),
+    }
+}
+
+/// Produce the row indexes that order the arrays by each key in turn. The sort is stable, so rows
+/// which are equal on every key keep their original order. NaN is the greatest value.
+pub fn sort_indices(keys: &[(ArrayRef, Sort, Nulls)]) -> Result<Vec<usize>, crate::Error> {
+    sort_indices_with_nans(keys, NanOrdering::Largest)
+}
+
+/// Produce the sorted row indexes with the given ordering of NaN
+pub fn sort_indices_with_nans(
+    keys: &[(ArrayRef, Sort, Nulls)],
+    nans: NanOrdering,
+) -> Result<Vec<usize>, crate::Error> {
+    let len = keys.first().map(|(arr, _, _)| arr.len()).unwrap_or(0);
+
+    let comparators = keys
+        .iter()
+        .map(|(arr, sort, nulls)| Ok((is_missing(arr, nans)?, comparator(arr)?, *sort, *nulls)))
+        .collect::<Result<Vec<_>, crate::Error>>()?;
+
+    let mut indices: Vec<usize> = (0..len).collect();
+
+    indices.sort_by(|&i, &j| {
+        for (is_missing, cmp, sort, nulls) in comparators.iter() {
+            let order = match (is_missing(i), is_missing(j)) {
+                (true, true) => Ordering::Equal,
+                (true, false) if *nulls == Nulls::First => Ordering::Less,
+                (true, false) => Ordering::Greater,
+                (false, true) if *nulls == Nulls::First => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) if *sort == Sort::DESC => cmp(i, j).reverse(),
+                (false, false) => cmp(i, j),
+            };
+
+            if order != Ordering::Equal {
+                return order;
+            }
+        }
+        Ordering::Equal
+    });
+
+    Ok(indices)
+}
+
+/// Sort every column of the view together by the keys
+pub fn sort(view: &View, keys: Vec<(Column, Sort, Nulls)>) -> Result<View, crate::Error> {
+    sort_with_nans(view, keys, NanOrdering::Largest)
+}
+
+/// Sort the view, NaN is either the greatest value or placed with the nulls
+pub fn sort_with_nans(
+    view: &View,
+    keys: Vec<(Column, Sort, Nulls)>,
+    nans: NanOrdering,
+) -> Result<View, crate::Error> {
+    let keys = keys
+        .into_iter()
+        .map(|(column, sort, nulls)| -> Result<_, crate::Error> {
+            let arr = view
+                .chunked_column(&column)
+                .ok_or(Error::InvalidColumn { column })?;
+            Ok((arr.to_array()?, sort, nulls))
+        })
+        .collect::<Result<Vec<_>, crate::Error>>()?;
+
+    let indices = sort_indices_with_nans(&keys, nans)?;
+
+    let columns = view
+        .columns()
+        .iter()
+        .map(|arr| take_rows(&arr.to_array()?, &indices))
+        .collect::<Result<Vec<_>, crate::Error>>()?;
+
+    Ok(View::new(view.fields().clone(), columns))
+}
+
+#[cfg(test)]
+mod test_sort {
+    use super::*;
+    use crate::{as_array, col, Field};
+    use arrow::array::UInt8Array;
+    use std::sync::Arc;
+
+    fn sample_view() -> View {
+        View::new(
+            vec![
+                Field::new("a", DataType::UInt8, true),
+                Field::new("b", DataType::Utf8, false),
+            ],
+            vec![
+                Arc::new(UInt8Array::from(vec![Some(2), None, Some(1), Some(2)])) as ArrayRef,
+                Arc::new(BinaryArray::from(vec!["x", "y", "z", "w"])) as ArrayRef,
+            ],
+        )
+    }
+
+    fn utf8_values(arr: &ArrayRef) -> Vec<&[u8]> {
+        let arr = as_array!(arr, BinaryArray).unwrap();
+        (0..arr.len()).map(|i| arr.value(i)).collect()
+    }
+
+    #[test]
+    fn it_sorts_by_multiple_keys() {
+        let view = sort(
+            &sample_view(),
+            vec![
+                (col!("a"), Sort::ASC, Nulls::Last),
+                (col!("b"), Sort::DESC, Nulls::Last),
+            ],
+        )
+        .unwrap();
+
+        let a = view.column(&col!("a")).unwrap();
+        let expected_a = UInt8Array::from(vec![Some(1), Some(2), Some(2), None]);
+        assert_eq!(as_array!(a, UInt8Array).unwrap(), &expected_a);
+
+        let b = view.column(&col!("b")).unwrap();
+        assert_eq!(
+            utf8_values(&b),
+            vec![b"z" as &[u8], b"x" as &[u8], b"w" as &[u8], b"y" as &[u8]]
+        );
+    }
+
+    #[test]
+    fn it_places_nulls_first() {
+        let view = sort(&sample_view(), vec![(col!("a"), Sort::DESC, Nulls::First)]).unwrap();
+
+        let a = view.column(&col!("a")).unwrap();
+        let expected_a = UInt8Array::from(vec![None, Some(2), Some(2), Some(1)]);
+        assert_eq!(as_array!(a, UInt8Array).unwrap(), &expected_a);
+
+        // the sort is stable
+        let b = view.column(&col!("b")).unwrap();
+        assert_eq!(
+            utf8_values(&b),
+            vec![b"y" as &[u8], b"x" as &[u8], b"w" as &[u8], b"z" as &[u8]]
+        );
+    }
+
+    #[test]
+    fn it_sorts_nan() {
+        use arrow::array::Float64Array;
+
+        let keys = vec![(
+            Arc::new(Float64Array::from(vec![
+                Some(std::f64::NAN),
+                Some(2.0),
+                None,
+                Some(std::f64::NEG_INFINITY),
+            ])) as ArrayRef,
+            Sort::ASC,
+            Nulls::First,
+        )];
+
+        assert_eq!(sort_indices(&keys).unwrap(), vec![2, 3, 1, 0]);
+        assert_eq!(
+            sort_indices_with_nans(&keys, NanOrdering::Null).unwrap(),
+            vec![0, 2, 3, 1]
+        );
+    }
+
+    #[test]
+    fn it_parses_the_sort_direction() {
+        assert_eq!(Sort::from_str("ASC").unwrap(), Sort::ASC);
+        assert_eq!(Sort::from_str("desc").unwrap(), Sort::DESC);
+        assert!(Sort::from_str("up").is_err());
+    }
 }
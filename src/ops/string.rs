@@ -0,0 +1,448 @@
+use crate::{as_array, ArrayRef, DataType};
+use arrow::array::{Array, BinaryArray, BinaryBuilder, BooleanArray, UInt64Builder};
+use bstr::ByteSlice;
+use regex::bytes::Regex;
+use snafu::Snafu;
+use std::sync::Arc;
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("String operations require a Utf8 array, found {:?}", data_type))]
+    InvalidStringType { data_type: DataType },
+
+    #[snafu(display(
+        "The pattern {} is not a valid regular expression: {}",
+        pattern,
+        source
+    ))]
+    InvalidRegex {
+        pattern: String,
+        source: regex::Error,
+    },
+}
+
+/// A pattern which is matched against every value of a Utf8 array
+#[derive(Clone, Debug)]
+pub enum Pattern<'a> {
+    Contains(&'a str),
+    StartsWith(&'a str),
+    EndsWith(&'a str),
+    /// SQL `LIKE`, `%` matches any sequence of characters and `_` matches a single character. The
+    /// wildcards can be escaped with a backslash.
+    Like(&'a str),
+    /// Case-insensitive `LIKE`
+    ILike(&'a str),
+    Regex(&'a str),
+}
+
+impl<'a> Pattern<'a> {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Contains(_) => "contains",
+            Self::StartsWith(_) => "starts_with",
+            Self::EndsWith(_) => "ends_with",
+            Self::Like(_) => "like",
+            Self::ILike(_) => "ilike",
+            Self::Regex(_) => "regexp_match",
+        }
+    }
+
+    /// Create the pattern from its function name, see `as_str`
+    pub fn from_name(name: &str, pattern: &'a str) -> Option<Pattern<'a>> {
+        match name {
+            "contains" => Some(Self::Contains(pattern)),
+            "starts_with" => Some(Self::StartsWith(pattern)),
+            "ends_with" => Some(Self::EndsWith(pattern)),
+            "like" => Some(Self::Like(pattern)),
+            "ilike" => Some(Self::ILike(pattern)),
+            "regexp_match" => Some(Self::Regex(pattern)),
+            _ => None,
+        }
+    }
+}
+
+/// Test each value of the Utf8 array against the pattern, null values produce a null
+pub fn matches(arr: &ArrayRef, pattern: &Pattern) -> Result<BooleanArray, crate::Error> {
+    let arr = utf8_array(arr)?;
+
+    match pattern {
+        Pattern::Contains(s) => map_values(arr, |v| v.contains_str(s)),
+        Pattern::StartsWith(s) => map_values(arr, |v| v.starts_with_str(s)),
+        Pattern::EndsWith(s) => map_values(arr, |v| v.ends_with_str(s)),
+        Pattern::Like(s) if !has_wildcards(s) => map_values(arr, |v| v == s.as_bytes()),
+        Pattern::ILike(s) if !has_wildcards(s) => map_values(arr, |v| match v.to_str() {
+            Ok(v) => unicase::eq(v, *s),
+            Err(_) => false,
+        }),
+        Pattern::Like(s) => {
+            let regex = compile(&like_to_regex(s, false))?;
+            map_values(arr, |v| regex.is_match(v))
+        }
+        Pattern::ILike(s) => {
+            let regex = compile(&like_to_regex(s, true))?;
+            map_values(arr, |v| regex.is_match(v))
+        }
+        Pattern::Regex(s) => {
+            let regex = compile(s)?;
+            map_values(arr, |v| regex.is_match(v))
+        }
+    }
+}
+
+pub fn upper(arr: &ArrayRef) -> Result<ArrayRef, crate::Error> {
+    map_strings(arr, |v| v.to_uppercase())
+}
+
+pub fn lower(arr: &ArrayRef) -> Result<ArrayRef, crate::Error> {
+    map_strings(arr, |v| v.to_lowercase())
+}
+
+/// Remove the leading and trailing whitespace
+pub fn trim(arr: &ArrayRef) -> Result<ArrayRef, crate::Error> {
+    map_strings(arr, |v| v.trim().to_vec())
+}
+
+/// The characters from the 1-based `start` position, up to `len` characters when it is set. As in
+/// SQL a start before the first character shortens the substring rather than shifting it.
+pub fn substring(arr: &ArrayRef, start: i64, len: Option<u64>) -> Result<ArrayRef, crate::Error> {
+    let first = start - 1;
+    let last = len.map(|len| first.saturating_add(len as i64));
+
+    map_strings(arr, |v| {
+        let mut out = Vec::new();
+
+        for (i, (from, to, _)) in v.char_indices().enumerate() {
+            let i = i as i64;
+            if last.map(|last| i >= last).unwrap_or(false) {
+                break;
+            }
+            if i >= first {
+                out.extend_from_slice(&v[from..to]);
+            }
+        }
+
+        out
+    })
+}
+
+/// The number of characters of each value
+pub fn length(arr: &ArrayRef) -> Result<ArrayRef, crate::Error> {
+    let arr = utf8_array(arr)?;
+    let mut builder = UInt64Builder::new(arr.len());
+
+    for i in 0..arr.len() {
+        if arr.is_null(i) {
+            builder.append_null()?;
+        } else {
+            builder.append_value(arr.value(i).chars().count() as u64)?;
+        }
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+/// Concatenate the values of each row, the row is null when any of the values are null
+pub fn concat(arrays: &[ArrayRef]) -> Result<ArrayRef, crate::Error> {
+    let arrays = arrays
+        .iter()
+        .map(utf8_array)
+        .collect::<Result<Vec<&BinaryArray>, crate::Error>>()?;
+
+    let len = arrays.first().map(|arr| arr.len()).unwrap_or(0);
+    let mut builder = BinaryBuilder::new(len);
+    let mut value = Vec::new();
+
+    for i in 0..len {
+        if arrays.iter().any(|arr| arr.is_null(i)) {
+            builder.append_null()?;
+            continue;
+        }
+
+        value.clear();
+        for arr in arrays.iter() {
+            value.extend_from_slice(arr.value(i));
+        }
+        builder.append_value(&value)?;
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+/// Replace every occurrence of `from` with `to`
+pub fn replace(arr: &ArrayRef, from: &[u8], to: &[u8]) -> Result<ArrayRef, crate::Error> {
+    map_strings(arr, |v| v.replace(from, to))
+}
+
+/// Split the value on the delimiter and take the 1-based `n`th part, the value is empty when
+/// there are fewer parts
+pub fn split_part(arr: &ArrayRef, delimiter: &[u8], n: usize) -> Result<ArrayRef, crate::Error> {
+    map_strings(arr, |v| match n {
+        0 => vec![],
+        n => v
+            .split_str(delimiter)
+            .nth(n - 1)
+            .map(|part| part.to_vec())
+            .unwrap_or_default(),
+    })
+}
+
+fn utf8_array(arr: &ArrayRef) -> Result<&BinaryArray, crate::Error> {
+    if arr.data_type() != &DataType::Utf8 {
+        return Err(Error::InvalidStringType {
+            data_type: arr.data_type().clone(),
+        }
+        .into());
+    }
+
+    as_array!(arr, BinaryArray)
+}
+
+/// Transform each value of the Utf8 array, nulls stay null
+fn map_strings<F>(arr: &ArrayRef, f: F) -> Result<ArrayRef, crate::Error>
+where
+    F: Fn(&[u8]) -> Vec<u8>,
+{
+    let arr = utf8_array(arr)?;
+    let mut builder = BinaryBuilder::new(arr.len());
+
+    for i in 0..arr.len() {
+        if arr.is_null(i) {
+            builder.append_null()?;
+        } else {
+            builder.append_value(&f(arr.value(i)))?;
+        }
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+fn map_values<F>(arr: &BinaryArray, f: F) -> Result<BooleanArray, crate::Error>
+where
+    F: Fn(&[u8]) -> bool,
+{
+    let mut builder = BooleanArray::builder(arr.len());
+
+    for i in 0..arr.len() {
+        if arr.is_null(i) {
+            builder.append_null()?;
+        } else {
+            builder.append_value(f(arr.value(i)))?;
+        }
+    }
+
+    Ok(builder.finish())
+}
+
+fn compile(pattern: &str) -> Result<Regex, crate::Error> {
+    Regex::new(pattern).map_err(|source| {
+        Error::InvalidRegex {
+            pattern: pattern.to_string(),
+            source,
+        }
+        .into()
+    })
+}
+
+fn has_wildcards(pattern: &str) -> bool {
+    pattern.contains(|c| c == '%' || c == '_' || c == '\\')
+}
+
+/// Translate the `LIKE` pattern to an anchored regular expression
+fn like_to_regex(pattern: &str, case_insensitive: bool) -> String {
+    let mut regex = String::with_capacity(pattern.len() + 8);
+
+    if case_insensitive {
+        regex.push_str("(?i)");
+    }
+
+    // `.` must also match new lines, which is the behaviour of `%` and `_`
+    regex.push_str("(?s)^");
+
+    let mut chars = pattern.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '%' => regex.push_str(".*"),
+            '_' => regex.push('.'),
+            '\\' => match chars.next() {
+                Some(escaped) => regex.push_str(&regex::escape(&escaped.to_string())),
+                None => regex.push_str(&regex::escape("\\")),
+            },
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+
+    regex.push('$');
+    regex
+}
+
+#[cfg(test)]
+mod test_string {
+    use super::*;
+
+    fn sample() -> ArrayRef {
+        Arc::new(BinaryArray::from(vec![
+            "GET /index.html",
+            "POST /api/users",
+            "get /api/users_1",
+            "DELETE /api/users",
+        ]))
+    }
+
+    fn matched(pattern: Pattern) -> Vec<bool> {
+        let res = matches(&sample(), &pattern).unwrap();
+        (0..res.len()).map(|i| res.value(i)).collect()
+    }
+
+    #[test]
+    fn it_matches_substrings() {
+        assert_eq!(
+            matched(Pattern::Contains("/api")),
+            vec![false, true, true, true]
+        );
+        assert_eq!(
+            matched(Pattern::StartsWith("GET")),
+            vec![true, false, false, false]
+        );
+        assert_eq!(
+            matched(Pattern::EndsWith("users")),
+            vec![false, true, false, true]
+        );
+    }
+
+    #[test]
+    fn it_matches_like_patterns() {
+        assert_eq!(
+            matched(Pattern::Like("%/api/users")),
+            vec![false, true, false, true]
+        );
+        assert_eq!(
+            matched(Pattern::Like("%users\\__")),
+            vec![false, false, true, false]
+        );
+        assert_eq!(
+            matched(Pattern::ILike("get %")),
+            vec![true, false, true, false]
+        );
+        assert_eq!(
+            matched(Pattern::ILike("post /API/users")),
+            vec![false, true, false, false]
+        );
+    }
+
+    #[test]
+    fn it_matches_regex() {
+        assert_eq!(
+            matched(Pattern::Regex("^(GET|POST) ")),
+            vec![true, true, false, false]
+        );
+        assert!(matches(&sample(), &Pattern::Regex("(")).is_err());
+    }
+
+    fn values(arr: ArrayRef) -> Vec<Option<String>> {
+        let arr = as_array!(arr, BinaryArray).unwrap();
+        (0..arr.len())
+            .map(|i| {
+                if arr.is_null(i) {
+                    None
+                } else {
+                    Some(arr.value(i).to_str_lossy().into_owned())
+                }
+            })
+            .collect()
+    }
+
+    fn strings(values: Vec<Option<&str>>) -> ArrayRef {
+        let mut builder = BinaryBuilder::new(values.len());
+        for value in values {
+            match value {
+                Some(v) => builder.append_value(v.as_bytes()).unwrap(),
+                None => builder.append_null().unwrap(),
+            }
+        }
+        Arc::new(builder.finish())
+    }
+
+    fn some(values: Vec<&str>) -> Vec<Option<String>> {
+        values.into_iter().map(|v| Some(v.to_string())).collect()
+    }
+
+    #[test]
+    fn it_transforms_case_and_whitespace() {
+        let arr = strings(vec![Some("  Straße "), None, Some("ÉtÉ")]);
+
+        assert_eq!(
+            values(upper(&arr).unwrap()),
+            vec![
+                Some("  STRASSE ".to_string()),
+                None,
+                Some("ÉTÉ".to_string())
+            ]
+        );
+        assert_eq!(
+            values(lower(&arr).unwrap()),
+            vec![Some("  straße ".to_string()), None, Some("été".to_string())]
+        );
+        assert_eq!(
+            values(trim(&arr).unwrap()),
+            vec![Some("Straße".to_string()), None, Some("ÉtÉ".to_string())]
+        );
+    }
+
+    #[test]
+    fn it_takes_substrings_by_character() {
+        let arr = strings(vec![Some("héllo"), Some("ab")]);
+
+        assert_eq!(
+            values(substring(&arr, 2, Some(3)).unwrap()),
+            some(vec!["éll", "b"])
+        );
+        assert_eq!(
+            values(substring(&arr, 0, Some(2)).unwrap()),
+            some(vec!["h", "a"])
+        );
+        assert_eq!(
+            values(substring(&arr, 3, None).unwrap()),
+            some(vec!["llo", ""])
+        );
+
+        let lengths = length(&arr).unwrap();
+        assert_eq!(
+            as_array!(lengths, arrow::array::UInt64Array).unwrap(),
+            &arrow::array::UInt64Array::from(vec![5, 2])
+        );
+    }
+
+    #[test]
+    fn it_concatenates_replaces_and_splits() {
+        let first = strings(vec![Some("a"), Some("b"), None]);
+        let second = strings(vec![Some("-x"), Some("-y"), Some("-z")]);
+
+        assert_eq!(
+            values(concat(&[first, second.clone()]).unwrap()),
+            vec![Some("a-x".to_string()), Some("b-y".to_string()), None]
+        );
+        assert_eq!(
+            values(replace(&second, b"-", b"+").unwrap()),
+            some(vec!["+x", "+y", "+z"])
+        );
+
+        let paths = strings(vec![Some("/api/users/1"), Some("/index")]);
+        assert_eq!(
+            values(split_part(&paths, b"/", 3).unwrap()),
+            some(vec!["users", ""])
+        );
+    }
+
+    #[test]
+    fn it_keeps_nulls() {
+        let mut builder = arrow::array::BinaryBuilder::new(2);
+        builder.append_value(b"a").unwrap();
+        builder.append_null().unwrap();
+        let arr: ArrayRef = Arc::new(builder.finish());
+
+        let res = matches(&arr, &Pattern::Contains("a")).unwrap();
+        assert!(res.value(0));
+        assert!(res.is_null(1));
+    }
+}
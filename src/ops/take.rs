@@ -0,0 +1,124 @@
+use crate::{as_array, Error};
+use arrow::array::{
+    Array, ArrayRef, BinaryArray, BinaryBuilder, BooleanArray, PrimitiveArray, PrimitiveBuilder,
+};
+use arrow::datatypes::{self as dt, ArrowNumericType, DataType, DateUnit, IntervalUnit, TimeUnit};
+use std::sync::Arc;
+
+macro_rules! take {
+    ($arr:ident, $indices:ident, $( [$dt:pat, $ty:ty] ),*) => {
+        match $arr.data_type() {
+            $($dt => take_primitive(as_array!($arr, PrimitiveArray<$ty>)?, $indices),)+
+            DataType::Boolean => take_boolean(as_array!($arr, BooleanArray)?, $indices),
+            DataType::Utf8 => take_utf8(as_array!($arr, BinaryArray)?, $indices),
+            data_type => Err(Error::UnknownDataType {
+                data_type: data_type.clone(),
+            }),
+        }
+    };
+}
+
+/// Gather the values at the `indices` into a new array, a `None` index produces a null
+pub fn take(arr: &ArrayRef, indices: &[Option<usize>]) -> Result<ArrayRef, Error> {
+    take!(
+        arr,
+        indices,
+        [DataType::UInt8, dt::UInt8Type],
+        [DataType::UInt16, dt::UInt16Type],
+        [DataType::UInt32, dt::UInt32Type],
+        [DataType::UInt64, dt::UInt64Type],
+        [DataType::Int8, dt::Int8Type],
+        [DataType::Int16, dt::Int16Type],
+        [DataType::Int32, dt::Int32Type],
+        [DataType::Int64, dt::Int64Type],
+        [DataType::Float32, dt::Float32Type],
+        [DataType::Float64, dt::Float64Type],
+        [DataType::Date32(DateUnit::Day), dt::Date32Type],
+        [DataType::Date64(DateUnit::Millisecond), dt::Date64Type],
+        [DataType::Time32(TimeUnit::Second), dt::Time32SecondType],
+        [
+            DataType::Time32(TimeUnit::Millisecond),
+            dt::Time32MillisecondType
+        ],
+        [
+            DataType::Time64(TimeUnit::Microsecond),
+            dt::Time64MicrosecondType
+        ],
+        [
+            DataType::Time64(TimeUnit::Nanosecond),
+            dt::Time64NanosecondType
+        ],
+        [
+            DataType::Timestamp(TimeUnit::Second),
+            dt::TimestampSecondType
+        ],
+        [
+            DataType::Timestamp(TimeUnit::Millisecond),
+            dt::TimestampMillisecondType
+        ],
+        [
+            DataType::Timestamp(TimeUnit::Microsecond),
+            dt::TimestampMicrosecondType
+        ],
+        [
+            DataType::Timestamp(TimeUnit::Nanosecond),
+            dt::TimestampNanosecondType
+        ],
+        [
+            DataType::Interval(IntervalUnit::YearMonth),
+            dt::IntervalYearMonthType
+        ],
+        [
+            DataType::Interval(IntervalUnit::DayTime),
+            dt::IntervalDayTimeType
+        ]
+    )
+}
+
+/// Gather the values at the `indices`, every index must be in bounds
+pub fn take_rows(arr: &ArrayRef, indices: &[usize]) -> Result<ArrayRef, Error> {
+    let indices: Vec<Option<usize>> = indices.iter().map(|i| Some(*i)).collect();
+    take(arr, &indices)
+}
+
+fn take_primitive<T: ArrowNumericType>(
+    arr: &PrimitiveArray<T>,
+    indices: &[Option<usize>],
+) -> Result<ArrayRef, Error> {
+    let mut builder = PrimitiveBuilder::<T>::new(indices.len());
+
+    for index in indices {
+        match index {
+            Some(i) if arr.is_valid(*i) => builder.append_value(arr.value(*i))?,
+            _ => builder.append_null()?,
+        }
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+fn take_boolean(arr: &BooleanArray, indices: &[Option<usize>]) -> Result<ArrayRef, Error> {
+    let mut builder = BooleanArray::builder(indices.len());
+
+    for index in indices {
+        match index {
+            Some(i) if arr.is_valid(*i) => builder.append_value(arr.value(*i))?,
+            _ => builder.append_null()?,
+        }
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+fn take_utf8(arr: &BinaryArray, indices: &[Option<usize>]) -> Result<ArrayRef, Error> {
+    let mut builder = BinaryBuilder::new(indices.len());
+
+    for index in indices {
+        match index {
+            Some(i) if arr.is_valid(*i) => builder.append_value(arr.value(*i))?,
+            _ => builder.append_null()?,
+        }
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
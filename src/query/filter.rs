@@ -1,12 +1,21 @@
+use crate::chunked::align_chunks;
 use crate::column::Column;
-use crate::datatype::ScalarValue;
-use crate::ops::cmp::{cmp_arrays, cmp_bool_arrays, CmpOp};
-use crate::{as_array, DataType, View};
+use crate::datatype::{cast_array, is_lossy_promotion, promote, to_numbers, Number, ScalarValue};
+use crate::expr::{evaluate_boolean_with_options, Expr};
+use crate::ops::boolean;
+use crate::ops::cmp::{cmp_value, compare, CmpOp, NanOrdering};
+use crate::ops::concat::concat_with_type;
+use crate::ops::hash::row_keys;
+use crate::ops::string::{matches, Pattern};
+use crate::ops::take::take_rows;
+use crate::query::QueryOptions;
+use crate::{as_array, ChunkedArray, DataType, View};
 
-use arrow::array::{Array, ArrayRef, BooleanArray, PrimitiveArray};
-use arrow::compute::kernels::boolean;
+use arrow::array::{Array, ArrayRef, BinaryArray, BooleanArray, PrimitiveArray};
 use arrow::datatypes as dt;
 use snafu::Snafu;
+use std::collections::HashSet;
+use std::sync::Arc;
 
 #[derive(Debug, Snafu)]
 pub enum Error {
@@ -29,71 +38,153 @@ pub enum Error {
     },
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum Filter<'a> {
     Columns(Column, CmpOp, Column),
     Scalar(Column, CmpOp, ScalarValue<'a>),
     And(Box<Filter<'a>>, Box<Filter<'a>>),
     Or(Box<Filter<'a>>, Box<Filter<'a>>),
+    Not(Box<Filter<'a>>),
+    IsNull(Column),
+    IsNotNull(Column),
+    /// Whether the value of the column is one of the values, the values are hashed so the column
+    /// is only scanned once however long the list is
+    In(Column, Vec<ScalarValue<'a>>),
+    NotIn(Column, Vec<ScalarValue<'a>>),
+    /// Whether the value of the column is within the inclusive range `low..=high`
+    Between(Column, ScalarValue<'a>, ScalarValue<'a>),
+    /// A string pattern such as `LIKE` which is matched against a Utf8 column
+    Pattern(Column, Pattern<'a>),
+    /// An expression which evaluates to a boolean array
+    Expr(Expr<'a>),
 }
 
 pub fn filter<'a>(view: &View, f: Filter<'a>) -> Result<View, crate::Error> {
-    let bool_array = filter_view(view, f)?;
+    filter_with_options(view, f, &QueryOptions::default())
+}
+
+/// Filter the rows, the options are used when evaluating expressions
+pub fn filter_with_options<'a>(
+    view: &View,
+    f: Filter<'a>,
+    options: &QueryOptions,
+) -> Result<View, crate::Error> {
+    // rows where the filter is null are unknown, so like SQL they are not kept
+    let bool_array = boolean::null_as_false(&filter_view(view, f, options)?)?;
 
-    let res: Result<Vec<ArrayRef>, _> = view
+    let res: Result<Vec<ChunkedArray>, _> = view
         .columns()
         .iter()
-        .map(|arr| apply_filter(&arr, &bool_array))
+        .map(|arr| filter_chunks(&arr, &bool_array))
         .collect();
 
-    Ok(View::new(view.fields().clone(), res?))
+    Ok(View::from_chunks(view.fields().clone(), res?))
+}
+
+/// Filter each chunk by the part of the mask which lines up with it, so the chunks are kept
+fn filter_chunks(arr: &ChunkedArray, mask: &BooleanArray) -> Result<ChunkedArray, crate::Error> {
+    if arr.num_chunks() == 1 {
+        return Ok(ChunkedArray::from(apply_filter(&arr.chunks()[0], mask)?));
+    }
+
+    let mut chunks = Vec::with_capacity(arr.num_chunks());
+    let mut offset = 0;
+
+    for chunk in arr.chunks() {
+        let mut chunk_mask = BooleanArray::builder(chunk.len());
+
+        for i in offset..offset + chunk.len() {
+            chunk_mask.append_value(mask.value(i))?;
+        }
+
+        chunks.push(apply_filter(chunk, &chunk_mask.finish())?);
+        offset += chunk.len();
+    }
+
+    ChunkedArray::new(arr.data_type().clone(), chunks)
 }
 
-fn filter_view<'a>(view: &View, f: Filter<'a>) -> Result<BooleanArray, crate::Error> {
+fn filter_view<'a>(
+    view: &View,
+    f: Filter<'a>,
+    options: &QueryOptions,
+) -> Result<BooleanArray, crate::Error> {
     match f {
         Filter::And(lhs, rhs) => {
-            let lhs_res = filter_view(&view, *lhs)?;
-            let rhs_res = filter_view(&view, *rhs)?;
-            boolean::and(&lhs_res, &rhs_res).map_err(|e| e.into())
+            let lhs_res = filter_view(&view, *lhs, options)?;
+            let rhs_res = filter_view(&view, *rhs, options)?;
+            boolean::and(&lhs_res, &rhs_res)
         }
         Filter::Or(lhs, rhs) => {
-            let lhs_res = filter_view(&view, *lhs)?;
-            let rhs_res = filter_view(&view, *rhs)?;
-            boolean::or(&lhs_res, &rhs_res).map_err(|e| e.into())
+            let lhs_res = filter_view(&view, *lhs, options)?;
+            let rhs_res = filter_view(&view, *rhs, options)?;
+            boolean::or(&lhs_res, &rhs_res)
+        }
+        Filter::Not(f) => {
+            let res = filter_view(&view, *f, options)?;
+            boolean::not(&res)
         }
-        Filter::Columns(lhs, op, rhs) => filter_cols(&view, lhs, rhs, op),
-        Filter::Scalar(arr, op, value) => filter_scalar(&view, arr, value, op),
+        Filter::IsNull(column) => filter_nulls(&view, column, true),
+        Filter::IsNotNull(column) => filter_nulls(&view, column, false),
+        Filter::In(column, values) => filter_in(&view, column, &values, options.nans),
+        Filter::NotIn(column, values) => {
+            boolean::not(&filter_in(&view, column, &values, options.nans)?)
+        }
+        Filter::Between(column, low, high) => {
+            filter_between(&view, column, low, high, options.nans)
+        }
+        Filter::Columns(lhs, op, rhs) => filter_cols(&view, lhs, rhs, op, options.nans),
+        Filter::Scalar(arr, op, value) => filter_scalar(&view, arr, value, op, options.nans),
+        Filter::Pattern(column, pattern) => {
+            let arr = view
+                .chunked_column(&column)
+                .ok_or(Error::InvalidColumn { column })?;
+            mask_chunks(arr, |chunk| matches(chunk, &pattern))
+        }
+        Filter::Expr(expr) => evaluate_boolean_with_options(&view, &expr, options),
     }
 }
 
-macro_rules! filter_cols {
-    ($lhs:ident, $rhs:ident, $op:ident, $( [$dt:path, $ty:ty] ),*) => {
-        match ($lhs.data_type(), $rhs.data_type(), $op) {
-            $( ($dt, $dt, op) => {
-                cmp_arrays(
-                    as_array!($lhs, PrimitiveArray<$ty>).unwrap(),
-                    as_array!($rhs, PrimitiveArray<$ty>).unwrap(),
-                    op
-                )
-            } ,)+
-        (DataType::Boolean, DataType::Boolean, CmpOp::Eq) => cmp_bool_arrays(
-            as_array!($lhs, PrimitiveArray<dt::BooleanType>).unwrap(),
-            as_array!($rhs, PrimitiveArray<dt::BooleanType>).unwrap(),
-            CmpOp::Eq
-        ),
-        (DataType::Boolean, DataType::Boolean, CmpOp::NotEq) => cmp_bool_arrays(
-            as_array!($lhs, PrimitiveArray<dt::BooleanType>).unwrap(),
-            as_array!($rhs, PrimitiveArray<dt::BooleanType>).unwrap(),
-            CmpOp::NotEq
-        ),
-        (lhs_dt, rhs_dt, op) => Err(Error::InvalidComparison {
-                lhs: lhs_dt.clone(),
-                rhs: rhs_dt.clone(),
-                op,
-            }
-            .into()),
+/// Evaluate the predicate on each chunk of the column and join the masks, the values of the
+/// column are never concatenated
+fn mask_chunks<F>(arr: &ChunkedArray, f: F) -> Result<BooleanArray, crate::Error>
+where
+    F: Fn(&ArrayRef) -> Result<BooleanArray, crate::Error>,
+{
+    join_masks(arr.chunks().iter().map(f))
+}
+
+fn join_masks<I>(masks: I) -> Result<BooleanArray, crate::Error>
+where
+    I: Iterator<Item = Result<BooleanArray, crate::Error>>,
+{
+    let masks = masks
+        .map(|mask| mask.map(|mask| Arc::new(mask) as ArrayRef))
+        .collect::<Result<Vec<_>, crate::Error>>()?;
+
+    let mask = match masks.as_slice() {
+        [mask] => mask.clone(),
+        masks => concat_with_type(&DataType::Boolean, masks)?,
+    };
+
+    Ok(BooleanArray::from(mask.data()))
+}
+
+/// Test each row of the column for null, or for a value when `is_null` is false
+fn filter_nulls(view: &View, column: Column, is_null: bool) -> Result<BooleanArray, crate::Error> {
+    let arr = view
+        .chunked_column(&column)
+        .ok_or(Error::InvalidColumn { column })?;
+
+    mask_chunks(arr, |chunk| {
+        let mut bool_arr = BooleanArray::builder(chunk.len());
+
+        for i in 0..chunk.len() {
+            bool_arr.append_value(chunk.is_null(i) == is_null)?;
         }
-    }
+
+        Ok(bool_arr.finish())
+    })
 }
 
 fn filter_cols(
@@ -101,41 +192,35 @@ fn filter_cols(
     lhs: Column,
     rhs: Column,
     op: CmpOp,
+    nans: NanOrdering,
 ) -> Result<BooleanArray, crate::Error> {
-    let lhs_idx = view
-        .get_index(&lhs)
+    let lhs_arr = view
+        .chunked_column(&lhs)
         .ok_or(Error::InvalidColumn { column: lhs })?;
-    let rhs_idx = view
-        .get_index(&rhs)
+    let rhs_arr = view
+        .chunked_column(&rhs)
         .ok_or(Error::InvalidColumn { column: rhs })?;
 
-    let lhs_arr = view.column_unchecked(lhs_idx);
-    let rhs_arr = view.column_unchecked(rhs_idx);
-
-    filter_cols!(
-        lhs_arr,
-        rhs_arr,
-        op,
-        [DataType::UInt8, dt::UInt8Type],
-        [DataType::UInt16, dt::UInt16Type],
-        [DataType::UInt32, dt::UInt32Type],
-        [DataType::UInt64, dt::UInt64Type],
-        [DataType::Int8, dt::Int8Type],
-        [DataType::Int16, dt::Int16Type],
-        [DataType::Int32, dt::Int32Type],
-        [DataType::Int64, dt::Int64Type],
-        [DataType::Float32, dt::Float32Type],
-        [DataType::Float64, dt::Float64Type]
+    // the columns may be chunked at different rows, so they are compared in aligned pieces
+    join_masks(
+        align_chunks(&[lhs_arr, rhs_arr])
+            .iter()
+            .map(|piece| compare(&piece[0], &piece[1], op.clone(), nans)),
     )
 }
 
 macro_rules! apply_filter {
     ($arr:ident, $bool_arr:ident, $( [$dt:path, $ty:ty] ),*) => {
         match $arr.data_type() {
-            $($dt => arrow_filter(as_array!($arr, PrimitiveArray<$ty>).unwrap(), $bool_arr),)+
-             missing_impl => unimplemented!(
-                "filter has not yet been implemented for arrays of type {:?}",
-                missing_impl),
+            $($dt => arrow_filter(as_array!($arr, PrimitiveArray<$ty>)?, $bool_arr)
+                .map_err(|e| e.into()),)+
+            // the remaining types, such as Utf8, are gathered row by row
+            _ => {
+                let rows: Vec<usize> = (0..$bool_arr.len())
+                    .filter(|i| $bool_arr.value(*i))
+                    .collect();
+                take_rows($arr, &rows)
+            }
         }
     };
 }
@@ -157,24 +242,251 @@ fn apply_filter(arr: &ArrayRef, bool_arr: &BooleanArray) -> Result<ArrayRef, cra
         [DataType::Float32, dt::Float32Type],
         [DataType::Float64, dt::Float64Type]
     )
-    .map_err(|e| e.into())
+}
+
+/// The data type which the column and every non-null value can be widened to
+fn common_type(
+    data_type: &DataType,
+    values: &[&ScalarValue],
+    op: CmpOp,
+) -> Result<DataType, crate::Error> {
+    let mut common = data_type.clone();
+
+    for value in values.iter().filter(|value| !value.is_null()) {
+        let value_type = value.data_type().ok_or(Error::InvalidScalarType)?;
+
+        if value_type == &common {
+            continue;
+        }
+
+        common = promote(&common, value_type).ok_or_else(|| Error::InvalidComparison {
+            lhs: data_type.clone(),
+            rhs: value_type.clone(),
+            op: op.clone(),
+        })?;
+    }
+
+    Ok(common)
+}
+
+/// Whether widening the column and the values to their common type rounds some of them, they are
+/// then compared as a `Number` instead
+fn is_lossy(data_type: &DataType, values: &[&ScalarValue]) -> bool {
+    let mut types = vec![data_type];
+    types.extend(values.iter().filter_map(|value| value.data_type()));
+
+    types
+        .iter()
+        .any(|lhs| types.iter().any(|rhs| is_lossy_promotion(lhs, rhs)))
+}
+
+/// Following SQL a row which doesn't match any of the values is null, rather than false, when the
+/// values contain a null. With `NanOrdering::Null` a NaN, as a row or a value, counts as a null.
+fn filter_in(
+    view: &View,
+    column: Column,
+    values: &[ScalarValue],
+    nans: NanOrdering,
+) -> Result<BooleanArray, crate::Error> {
+    let arr = view
+        .chunked_column(&column)
+        .ok_or(Error::InvalidColumn { column })?;
+
+    let value_refs = values.iter().collect::<Vec<_>>();
+    let data_type = common_type(arr.data_type(), &value_refs, CmpOp::Eq)?;
+    let lossy = is_lossy(arr.data_type(), &value_refs);
+
+    // both sides are keyed alike, a rounded common type would let distinct integers match
+    let keys = |arr: &ArrayRef| -> Result<Vec<Vec<u8>>, crate::Error> {
+        if lossy {
+            Ok(to_numbers(arr)?.iter().map(Number::key).collect())
+        } else {
+            row_keys(&[cast_array(arr, &data_type)?])
+        }
+    };
+
+    let is_nan = |value: &Number| nans == NanOrdering::Null && value.is_nan();
+
+    let mut set: HashSet<Vec<u8>> = HashSet::with_capacity(values.len());
+    let mut has_null = false;
+
+    for value in values {
+        if value.is_null() || value.to_number().map_or(false, |value| is_nan(&value)) {
+            has_null = true;
+            continue;
+        }
+
+        set.extend(keys(&value.to_array(1)?)?);
+    }
+
+    mask_chunks(arr, |chunk| {
+        let mut bool_arr = BooleanArray::builder(chunk.len());
+
+        let nan_rows: Vec<bool> = match chunk.data_type() {
+            DataType::Float32 | DataType::Float64 => {
+                to_numbers(chunk)?.iter().map(is_nan).collect()
+            }
+            _ => vec![false; chunk.len()],
+        };
+
+        for (i, key) in keys(chunk)?.iter().enumerate() {
+            if chunk.is_null(i) || nan_rows[i] {
+                bool_arr.append_null()?;
+            } else if set.contains(key) {
+                bool_arr.append_value(true)?;
+            } else if has_null {
+                bool_arr.append_null()?;
+            } else {
+                bool_arr.append_value(false)?;
+            }
+        }
+
+        Ok(bool_arr.finish())
+    })
+}
+
+macro_rules! cmp_between {
+    ($arr:ident, $low:ident, $high:ident, $nans:ident, $( [$dt:path, $ty:ty, $fn:ident] ),*) => {
+        match $arr.data_type() {
+            $($dt => {
+                let arr = as_array!($arr, PrimitiveArray<$ty>)?;
+                let low = $low.$fn().ok_or(Error::InvalidScalarType)?;
+                let high = $high.$fn().ok_or(Error::InvalidScalarType)?;
+
+                eval_rows(arr, |i| {
+                    let value = arr.value(i);
+                    let above = cmp_value(&value, &low, &CmpOp::GtEq, $nans)?;
+                    let below = cmp_value(&value, &high, &CmpOp::LtEq, $nans)?;
+                    Some(above && below)
+                })
+            },)+
+            DataType::Utf8 => {
+                let arr = as_array!($arr, BinaryArray)?;
+                let low: &[u8] = $low.as_string().ok_or(Error::InvalidScalarType)?;
+                let high: &[u8] = $high.as_string().ok_or(Error::InvalidScalarType)?;
+
+                eval_rows(arr, |i| Some(low <= arr.value(i) && arr.value(i) <= high))
+            },
+            data_type => Err(Error::InvalidComparison {
+                lhs: data_type.clone(),
+                rhs: data_type.clone(),
+                op: CmpOp::GtEq,
+            }
+            .into()),
+        }
+    };
+}
+
+fn filter_between(
+    view: &View,
+    column: Column,
+    low: ScalarValue,
+    high: ScalarValue,
+    nans: NanOrdering,
+) -> Result<BooleanArray, crate::Error> {
+    let arr = view
+        .chunked_column(&column)
+        .ok_or(Error::InvalidColumn { column })?;
+
+    // a range with a missing bound can't be satisfied
+    if low.is_null() || high.is_null() {
+        return Ok(BooleanArray::from(vec![None; arr.len()]));
+    }
+
+    let data_type = common_type(arr.data_type(), &[&low, &high], CmpOp::GtEq)?;
+
+    if is_lossy(arr.data_type(), &[&low, &high]) {
+        let low = low.to_number().ok_or(Error::InvalidScalarType)?;
+        let high = high.to_number().ok_or(Error::InvalidScalarType)?;
+
+        return mask_chunks(arr, |chunk| {
+            let values = to_numbers(chunk)?;
+
+            eval_rows(chunk, |i| {
+                let above = cmp_value(&values[i], &low, &CmpOp::GtEq, nans)?;
+                let below = cmp_value(&values[i], &high, &CmpOp::LtEq, nans)?;
+                Some(above && below)
+            })
+        });
+    }
+
+    let low = low.cast(&data_type).ok_or(Error::InvalidScalarType)?;
+    let high = high.cast(&data_type).ok_or(Error::InvalidScalarType)?;
+
+    mask_chunks(arr, |chunk| {
+        let chunk = cast_array(chunk, &data_type)?;
+
+        cmp_between!(
+            chunk,
+            low,
+            high,
+            nans,
+            [DataType::UInt8, dt::UInt8Type, as_uint8],
+            [DataType::UInt16, dt::UInt16Type, as_uint16],
+            [DataType::UInt32, dt::UInt32Type, as_uint32],
+            [DataType::UInt64, dt::UInt64Type, as_uint64],
+            [DataType::Int8, dt::Int8Type, as_int8],
+            [DataType::Int16, dt::Int16Type, as_int16],
+            [DataType::Int32, dt::Int32Type, as_int32],
+            [DataType::Int64, dt::Int64Type, as_int64],
+            [DataType::Float32, dt::Float32Type, as_float32],
+            [DataType::Float64, dt::Float64Type, as_float64]
+        )
+    })
+}
+
+/// Evaluate each valid row of the array, null rows stay null
+fn eval_rows<F>(arr: &dyn Array, f: F) -> Result<BooleanArray, crate::Error>
+where
+    F: Fn(usize) -> Option<bool>,
+{
+    let mut bool_arr = BooleanArray::builder(arr.len());
+
+    for i in 0..arr.len() {
+        match f(i) {
+            Some(value) if arr.is_valid(i) => bool_arr.append_value(value)?,
+            _ => bool_arr.append_null()?,
+        }
+    }
+
+    Ok(bool_arr.finish())
 }
 
 macro_rules! cmp_scalar {
-    ($arr:ident, $scalar:ident, $bool_arr:ident, $op:ident, $( [$dt:path,  $ty:ty, $fn:ident] ),*) => {
+    ($arr:ident, $scalar:ident, $bool_arr:ident, $op:ident, $nans:ident, $( [$dt:path,  $ty:ty, $fn:ident] ),*) => {
         match($arr.data_type(), $scalar.data_type().unwrap())  {
             $(($dt, $dt) => {
                 let arr = as_array!($arr, PrimitiveArray<$ty>).unwrap();
                 let value = $scalar.$fn().unwrap();
 
                 for i in 0..arr.len() {
-                    $bool_arr.append_value($op.eq_ord(
-                        arr.value(i).partial_cmp(&value).unwrap()
-                    ))?;
+                    if arr.is_null(i) {
+                        $bool_arr.append_null()?;
+                        continue;
+                    }
+
+                    match cmp_value(&arr.value(i), &value, &$op, $nans) {
+                        Some(res) => $bool_arr.append_value(res)?,
+                        None => $bool_arr.append_null()?,
+                    }
                 }
 
                 Ok($bool_arr.finish())
             },)+
+            (DataType::Utf8, DataType::Utf8) => {
+                let arr = as_array!($arr, BinaryArray)?;
+                let value: &[u8] = $scalar.as_string().unwrap();
+
+                for i in 0..arr.len() {
+                    if arr.is_null(i) {
+                        $bool_arr.append_null()?;
+                    } else {
+                        $bool_arr.append_value($op.eq_ord(arr.value(i).cmp(value)))?;
+                    }
+                }
+
+                Ok($bool_arr.finish())
+            },
         (lhs, rhs) => Err(Error::InvalidComparison {
                 lhs: lhs.clone(),
                 rhs: rhs.clone(),
@@ -189,64 +501,78 @@ fn filter_scalar<'a>(
     column: Column,
     value: ScalarValue<'a>,
     op: CmpOp,
+    nans: NanOrdering,
 ) -> Result<BooleanArray, crate::error::Error> {
+    // comparing with null is treated as IS/IS NOT NULL, this must be checked before the data
+    // type as null values don't have one
+    if value.is_null() {
+        return match op {
+            CmpOp::Eq => filter_nulls(view, column, true),
+            CmpOp::NotEq => filter_nulls(view, column, false),
+            _ => Err(Error::InvalidNullComparison { op }.into()),
+        };
+    }
+
     if value.data_type().is_none() {
         return Err(Error::InvalidScalarType.into());
     }
 
-    let arr_idx = view
-        .get_index(&column)
+    let arr = view
+        .chunked_column(&column)
         .ok_or(Error::InvalidColumn { column })?;
-    let arr = view.column_unchecked(arr_idx);
 
-    // check for IS/IS NOT NULL
-    if value.is_null() {
-        return match op {
-            CmpOp::Eq | CmpOp::NotEq => {
-                // array of arr[index] IS NULL
-                let mut bool_arr = BooleanArray::builder(arr.len());
+    // widening would round the 64 bit integers, so the values are compared exactly
+    if is_lossy(arr.data_type(), &[&value]) {
+        let value = value.to_number().ok_or(Error::InvalidScalarType)?;
 
-                for i in 0..arr.len() {
-                    bool_arr.append_value(arr.is_null(i))?;
-                }
+        return mask_chunks(arr, |chunk| {
+            let values = to_numbers(chunk)?;
+            eval_rows(chunk, |i| cmp_value(&values[i], &value, &op, nans))
+        });
+    }
 
-                let bool_arr = bool_arr.finish();
+    // widen the column and the value to a common numeric type
+    let data_type = promote(arr.data_type(), value.data_type().unwrap());
 
-                if op == CmpOp::Eq {
-                    return Ok(bool_arr);
-                }
+    let value = match &data_type {
+        Some(data_type) => value.cast(data_type).ok_or(Error::InvalidScalarType)?,
+        None => value,
+    };
 
-                Ok(boolean::not(&bool_arr)?)
-            }
-            _ => Err(Error::InvalidNullComparison { op }.into()),
+    mask_chunks(arr, |chunk| {
+        let chunk = match &data_type {
+            Some(data_type) => cast_array(chunk, data_type)?,
+            None => chunk.clone(),
         };
-    }
 
-    let mut bool_arr = BooleanArray::builder(arr.len());
+        let mut bool_arr = BooleanArray::builder(chunk.len());
+        let op = op.clone();
 
-    cmp_scalar!(
-        arr,
-        value,
-        bool_arr,
-        op,
-        [DataType::Boolean, dt::BooleanType, as_boolean],
-        [DataType::UInt8, dt::UInt8Type, as_uint8],
-        [DataType::UInt16, dt::UInt16Type, as_uint16],
-        [DataType::UInt32, dt::UInt32Type, as_uint32],
-        [DataType::UInt64, dt::UInt64Type, as_uint64],
-        [DataType::Int8, dt::Int8Type, as_int8],
-        [DataType::Int16, dt::Int16Type, as_int16],
-        [DataType::Int32, dt::Int32Type, as_int32],
-        [DataType::Int64, dt::Int64Type, as_int64],
-        [DataType::Float32, dt::Float32Type, as_float32],
-        [DataType::Float64, dt::Float64Type, as_float64]
-    )
+        cmp_scalar!(
+            chunk,
+            value,
+            bool_arr,
+            op,
+            nans,
+            [DataType::Boolean, dt::BooleanType, as_boolean],
+            [DataType::UInt8, dt::UInt8Type, as_uint8],
+            [DataType::UInt16, dt::UInt16Type, as_uint16],
+            [DataType::UInt32, dt::UInt32Type, as_uint32],
+            [DataType::UInt64, dt::UInt64Type, as_uint64],
+            [DataType::Int8, dt::Int8Type, as_int8],
+            [DataType::Int16, dt::Int16Type, as_int16],
+            [DataType::Int32, dt::Int32Type, as_int32],
+            [DataType::Int64, dt::Int64Type, as_int64],
+            [DataType::Float32, dt::Float32Type, as_float32],
+            [DataType::Float64, dt::Float64Type, as_float64]
+        )
+    })
 }
 
 #[cfg(test)]
 mod test_filter {
     use super::*;
-    use crate::{array, view, DataType, Field, View};
+    use crate::{array, array_ref, view, DataType, Field, View};
     use arrow::array::UInt8Array;
     use arrow::datatypes as dt;
     use std::sync::Arc;
@@ -289,4 +615,495 @@ mod test_filter {
             assert_eq!(as_array!(b, UInt8Array).unwrap(), &expected_b);
         }
     }
+
+    #[test]
+    fn it_filters_with_promoted_types() {
+        let view = view!(
+            ["a", dt::Int32Type, [-2, 4, 5, 8, 10]],
+            ["b", dt::UInt8Type, [2, 4, 6, 8, 10]]
+        );
+
+        let filtered_view = filter(
+            &view,
+            Filter::Scalar("a".into(), CmpOp::Gt, ScalarValue::Int64(4)),
+        )
+        .unwrap();
+        assert_eq!(filtered_view.num_rows(), 3);
+
+        let filtered_view =
+            filter(&view, Filter::Columns("a".into(), CmpOp::Eq, "b".into())).unwrap();
+        assert_eq!(filtered_view.num_rows(), 3);
+
+        let a = filtered_view.column(&"a".into()).unwrap();
+        let expected_a = array!(dt::Int32Type, [4, 8, 10]);
+        assert_eq!(as_array!(a, arrow::array::Int32Array).unwrap(), &expected_a);
+    }
+
+    #[test]
+    fn it_filters_64_bit_integers_exactly() {
+        let view = view!([
+            "id",
+            dt::UInt64Type,
+            [9_007_199_254_740_993, 9_007_199_254_740_992, 1]
+        ]);
+        let id = ScalarValue::Int64(9_007_199_254_740_992);
+
+        let filters = vec![
+            Filter::Scalar("id".into(), CmpOp::Eq, id.clone()),
+            Filter::In("id".into(), vec![id.clone(), ScalarValue::Int64(-1)]),
+            Filter::Between(
+                "id".into(),
+                id.clone(),
+                ScalarValue::Float64(9.007_199_254_740_992e15),
+            ),
+        ];
+
+        for f in filters {
+            let filtered_view = filter(&view, f).unwrap();
+            let ids = filtered_view.column(&"id".into()).unwrap();
+            assert_eq!(
+                as_array!(ids, arrow::array::UInt64Array).unwrap(),
+                &array!(dt::UInt64Type, [9_007_199_254_740_992])
+            );
+        }
+    }
+
+    #[test]
+    fn it_filters_chunked_columns() {
+        let mut view = view!(["a", dt::Int32Type, [1, 6, 3]]);
+        view.append(&view!(["a", dt::Int32Type, [8, 2]])).unwrap();
+
+        let filtered_view = filter(
+            &view,
+            Filter::Scalar("a".into(), CmpOp::Gt, ScalarValue::Int32(2)),
+        )
+        .unwrap();
+
+        assert_eq!(
+            filtered_view
+                .chunked_column(&"a".into())
+                .unwrap()
+                .num_chunks(),
+            2
+        );
+
+        let a = filtered_view.column(&"a".into()).unwrap();
+        let expected_a = array!(dt::Int32Type, [6, 3, 8]);
+        assert_eq!(as_array!(a, arrow::array::Int32Array).unwrap(), &expected_a);
+    }
+
+    #[test]
+    fn it_filters_columns_chunked_at_different_rows() {
+        let a = ChunkedArray::new(
+            DataType::Int32,
+            vec![
+                array_ref!(dt::Int32Type, [1, 6, 3]),
+                array_ref!(dt::Int32Type, [8]),
+            ],
+        )
+        .unwrap();
+        let b = ChunkedArray::new(
+            DataType::Int32,
+            vec![
+                array_ref!(dt::Int32Type, [2]),
+                array_ref!(dt::Int32Type, [5, 4, 9]),
+            ],
+        )
+        .unwrap();
+
+        let view = View::from_chunks(
+            vec![
+                Field::new("a", DataType::Int32, false),
+                Field::new("b", DataType::Int32, false),
+            ],
+            vec![a, b],
+        );
+
+        let filtered_view =
+            filter(&view, Filter::Columns("a".into(), CmpOp::Gt, "b".into())).unwrap();
+
+        let a = filtered_view.column(&"a".into()).unwrap();
+        let expected_a = array!(dt::Int32Type, [6]);
+        assert_eq!(as_array!(a, arrow::array::Int32Array).unwrap(), &expected_a);
+    }
+
+    #[test]
+    fn it_filters_chunked_temporal_columns() {
+        let day = |values: Vec<Option<i32>>| {
+            Arc::new(arrow::array::Date32Array::from(values)) as ArrayRef
+        };
+        let fields = vec![Field::new("day", DataType::Date32(dt::DateUnit::Day), true)];
+
+        let mut view = View::new(fields.clone(), vec![day(vec![Some(1), None])]);
+        view.append(&View::new(fields, vec![day(vec![None, Some(4)])]))
+            .unwrap();
+
+        let filtered_view = filter(&view, Filter::IsNull("day".into())).unwrap();
+        assert_eq!(filtered_view.num_rows(), 2);
+        assert_eq!(
+            filtered_view
+                .chunked_column(&"day".into())
+                .unwrap()
+                .num_chunks(),
+            2
+        );
+
+        assert!(filtered_view.column(&"day".into()).is_ok());
+        assert!(filtered_view.column(&"missing".into()).is_err());
+    }
+
+    #[test]
+    fn it_filters_utf8_columns() {
+        let view = View::new(
+            vec![
+                Field::new("name", DataType::Utf8, false),
+                Field::new("alias", DataType::Utf8, false),
+                Field::new("age", DataType::UInt8, false),
+            ],
+            vec![
+                Arc::new(BinaryArray::from(vec!["bob", "alice", "carol", "dave"])) as ArrayRef,
+                Arc::new(BinaryArray::from(vec!["bob", "ally", "caz", "dave"])) as ArrayRef,
+                Arc::new(UInt8Array::from(vec![30, 25, 41, 19])) as ArrayRef,
+            ],
+        );
+
+        let filtered_view = filter(
+            &view,
+            Filter::Scalar("name".into(), CmpOp::Gt, ScalarValue::String("bob".into())),
+        )
+        .unwrap();
+
+        let name = filtered_view.column(&"name".into()).unwrap();
+        let name = as_array!(name, BinaryArray).unwrap();
+        assert_eq!(name.len(), 2);
+        assert_eq!(name.value(0), b"carol");
+        assert_eq!(name.value(1), b"dave");
+
+        let age = filtered_view.column(&"age".into()).unwrap();
+        let expected_age = array!(dt::UInt8Type, [41, 19]);
+        assert_eq!(as_array!(age, UInt8Array).unwrap(), &expected_age);
+
+        let filtered_view = filter(
+            &view,
+            Filter::Columns("name".into(), CmpOp::NotEq, "alias".into()),
+        )
+        .unwrap();
+
+        let alias = filtered_view.column(&"alias".into()).unwrap();
+        let alias = as_array!(alias, BinaryArray).unwrap();
+        assert_eq!(alias.len(), 2);
+        assert_eq!(alias.value(0), b"ally");
+        assert_eq!(alias.value(1), b"caz");
+    }
+
+    #[test]
+    fn it_filters_nulls() {
+        let mut names = arrow::array::BinaryBuilder::new(4);
+        names.append_value(b"ann").unwrap();
+        names.append_null().unwrap();
+        names.append_value(b"cy").unwrap();
+        names.append_null().unwrap();
+
+        let view = View::new(
+            vec![
+                Field::new("name", DataType::Utf8, true),
+                Field::new("age", DataType::UInt8, true),
+            ],
+            vec![
+                Arc::new(names.finish()) as ArrayRef,
+                Arc::new(UInt8Array::from(vec![Some(1), Some(2), None, None])) as ArrayRef,
+            ],
+        );
+
+        let filtered_view = filter(&view, Filter::IsNull("name".into())).unwrap();
+        let age = filtered_view.column(&"age".into()).unwrap();
+        assert_eq!(
+            as_array!(age, UInt8Array).unwrap(),
+            &UInt8Array::from(vec![Some(2), None])
+        );
+
+        let filtered_view = filter(
+            &view,
+            Filter::And(
+                Box::new(Filter::IsNotNull("name".into())),
+                Box::new(Filter::Not(Box::new(Filter::IsNotNull("age".into())))),
+            ),
+        )
+        .unwrap();
+        let name = filtered_view.column(&"name".into()).unwrap();
+        let name = as_array!(name, BinaryArray).unwrap();
+        assert_eq!(name.len(), 1);
+        assert_eq!(name.value(0), b"cy");
+
+        let filtered_view = filter(
+            &view,
+            Filter::Scalar("age".into(), CmpOp::NotEq, ScalarValue::Null),
+        )
+        .unwrap();
+        assert_eq!(filtered_view.num_rows(), 2);
+
+        assert!(filter(
+            &view,
+            Filter::Scalar("age".into(), CmpOp::Gt, ScalarValue::Null)
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn it_uses_three_valued_logic() {
+        let view = View::new(
+            vec![
+                Field::new("a", DataType::UInt8, true),
+                Field::new("b", DataType::UInt8, true),
+            ],
+            vec![
+                Arc::new(UInt8Array::from(vec![Some(1), None, Some(3), None])) as ArrayRef,
+                Arc::new(UInt8Array::from(vec![Some(1), Some(1), None, None])) as ArrayRef,
+            ],
+        );
+
+        // null > 2 is unknown, so negating it must not keep the row
+        let filtered_view = filter(
+            &view,
+            Filter::Not(Box::new(Filter::Scalar(
+                "a".into(),
+                CmpOp::Gt,
+                ScalarValue::UInt8(2),
+            ))),
+        )
+        .unwrap();
+        let b = filtered_view.column(&"b".into()).unwrap();
+        assert_eq!(
+            as_array!(b, UInt8Array).unwrap(),
+            &UInt8Array::from(vec![Some(1)])
+        );
+
+        // unknown OR true is true
+        let filtered_view = filter(
+            &view,
+            Filter::Or(
+                Box::new(Filter::Columns("a".into(), CmpOp::Eq, "b".into())),
+                Box::new(Filter::Scalar("b".into(), CmpOp::Eq, ScalarValue::UInt8(1))),
+            ),
+        )
+        .unwrap();
+        assert_eq!(filtered_view.num_rows(), 2);
+
+        // unknown AND false is false, negated it is true
+        let filtered_view = filter(
+            &view,
+            Filter::Not(Box::new(Filter::And(
+                Box::new(Filter::Scalar("a".into(), CmpOp::Eq, ScalarValue::UInt8(3))),
+                Box::new(Filter::Scalar("b".into(), CmpOp::Gt, ScalarValue::UInt8(1))),
+            ))),
+        )
+        .unwrap();
+        let a = filtered_view.column(&"a".into()).unwrap();
+        assert_eq!(
+            as_array!(a, UInt8Array).unwrap(),
+            &UInt8Array::from(vec![Some(1), None])
+        );
+    }
+
+    #[test]
+    fn it_filters_nan_with_a_total_order() {
+        use crate::query::QueryOptions;
+        use arrow::array::Float64Array;
+
+        let view = View::new(
+            vec![Field::new("reading", DataType::Float64, false)],
+            vec![Arc::new(Float64Array::from(vec![1.5, std::f64::NAN, 3.0])) as ArrayRef],
+        );
+
+        let f = || Filter::Scalar("reading".into(), CmpOp::Gt, ScalarValue::Float64(2.0));
+
+        let filtered_view = filter(&view, f()).unwrap();
+        assert_eq!(filtered_view.num_rows(), 2);
+
+        let options = QueryOptions {
+            nans: NanOrdering::Null,
+            ..QueryOptions::default()
+        };
+        let filtered_view = filter_with_options(&view, f(), &options).unwrap();
+        let reading = filtered_view.column(&"reading".into()).unwrap();
+        assert_eq!(
+            as_array!(reading, Float64Array).unwrap(),
+            &Float64Array::from(vec![3.0])
+        );
+    }
+
+    #[test]
+    fn it_filters_nan_in_lists_as_null() {
+        use crate::query::QueryOptions;
+        use arrow::array::Float64Array;
+
+        let view = View::new(
+            vec![Field::new("reading", DataType::Float64, false)],
+            vec![Arc::new(Float64Array::from(vec![1.5, std::f64::NAN, 3.0])) as ArrayRef],
+        );
+
+        let options = QueryOptions {
+            nans: NanOrdering::Null,
+            ..QueryOptions::default()
+        };
+        let nan = ScalarValue::Float64(std::f64::NAN);
+        let mask = |f| filter_view(&view, f, &options).unwrap();
+
+        let res = mask(Filter::In("reading".into(), vec![nan.clone()]));
+        assert_eq!(res, BooleanArray::from(vec![None, None, None]));
+
+        let res = mask(Filter::In(
+            "reading".into(),
+            vec![nan.clone(), ScalarValue::Float64(1.5)],
+        ));
+        assert_eq!(res, BooleanArray::from(vec![Some(true), None, None]));
+
+        let res = mask(Filter::NotIn(
+            "reading".into(),
+            vec![ScalarValue::Float64(1.5)],
+        ));
+        assert_eq!(res, BooleanArray::from(vec![Some(false), None, Some(true)]));
+
+        // by default NaN is a value like any other
+        let filtered_view = filter(&view, Filter::In("reading".into(), vec![nan])).unwrap();
+        assert_eq!(filtered_view.num_rows(), 1);
+    }
+
+    #[test]
+    fn it_filters_in_and_between() {
+        let mut names = arrow::array::BinaryBuilder::new(5);
+        for name in &["ann", "bo", "cy", "di"] {
+            names.append_value(name.as_bytes()).unwrap();
+        }
+        names.append_null().unwrap();
+
+        let view = View::new(
+            vec![
+                Field::new("name", DataType::Utf8, true),
+                Field::new("age", DataType::UInt8, true),
+            ],
+            vec![
+                Arc::new(names.finish()) as ArrayRef,
+                Arc::new(UInt8Array::from(vec![
+                    Some(18),
+                    Some(30),
+                    None,
+                    Some(45),
+                    Some(30),
+                ])) as ArrayRef,
+            ],
+        );
+
+        let filtered_view = filter(
+            &view,
+            Filter::In(
+                "age".into(),
+                vec![ScalarValue::Int64(30), ScalarValue::Int64(45)],
+            ),
+        )
+        .unwrap();
+        let age = filtered_view.column(&"age".into()).unwrap();
+        assert_eq!(
+            as_array!(age, UInt8Array).unwrap(),
+            &UInt8Array::from(vec![30, 45, 30])
+        );
+
+        let filtered_view = filter(
+            &view,
+            Filter::NotIn(
+                "name".into(),
+                vec![
+                    ScalarValue::String("bo".into()),
+                    ScalarValue::String("di".into()),
+                ],
+            ),
+        )
+        .unwrap();
+        assert_eq!(filtered_view.num_rows(), 2);
+
+        // a null in the list makes every row which isn't in it unknown
+        let filtered_view = filter(
+            &view,
+            Filter::NotIn(
+                "age".into(),
+                vec![ScalarValue::UInt8(30), ScalarValue::Null],
+            ),
+        )
+        .unwrap();
+        assert_eq!(filtered_view.num_rows(), 0);
+
+        let filtered_view = filter(
+            &view,
+            Filter::Between(
+                "age".into(),
+                ScalarValue::UInt8(20),
+                ScalarValue::Float64(45.0),
+            ),
+        )
+        .unwrap();
+        let age = filtered_view.column(&"age".into()).unwrap();
+        assert_eq!(
+            as_array!(age, UInt8Array).unwrap(),
+            &UInt8Array::from(vec![30, 45, 30])
+        );
+
+        let filtered_view = filter(
+            &view,
+            Filter::Between(
+                "name".into(),
+                ScalarValue::String("b".into()),
+                ScalarValue::String("cz".into()),
+            ),
+        )
+        .unwrap();
+        assert_eq!(filtered_view.num_rows(), 2);
+
+        assert!(filter(
+            &view,
+            Filter::In("name".into(), vec![ScalarValue::UInt8(1)])
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn it_filters_string_patterns() {
+        let view = View::new(
+            vec![
+                Field::new("path", DataType::Utf8, false),
+                Field::new("status", DataType::UInt16, false),
+            ],
+            vec![
+                Arc::new(BinaryArray::from(vec![
+                    "/api/users",
+                    "/index.html",
+                    "/API/orders",
+                    "/api/orders",
+                ])) as ArrayRef,
+                Arc::new(arrow::array::UInt16Array::from(vec![200, 200, 404, 500])) as ArrayRef,
+            ],
+        );
+
+        let f = Filter::And(
+            Box::new(Filter::Pattern("path".into(), Pattern::ILike("/api/%"))),
+            Box::new(Filter::Scalar(
+                "status".into(),
+                CmpOp::Gt,
+                ScalarValue::UInt16(200),
+            )),
+        );
+
+        let filtered_view = filter(&view, f).unwrap();
+        let path = filtered_view.column(&"path".into()).unwrap();
+        let path = as_array!(path, BinaryArray).unwrap();
+        assert_eq!(path.len(), 2);
+        assert_eq!(path.value(0), b"/API/orders");
+        assert_eq!(path.value(1), b"/api/orders");
+
+        let filtered_view = filter(
+            &view,
+            Filter::Pattern("path".into(), Pattern::Regex(r"\.html$")),
+        )
+        .unwrap();
+        assert_eq!(filtered_view.num_rows(), 1);
+    }
 }
@@ -0,0 +1,253 @@
+use crate::chunked::align_chunks;
+use crate::column::Column;
+use crate::ops::aggregate::aggregate;
+use crate::ops::hash::row_keys;
+use crate::ops::take::take_rows;
+use crate::query::select::{select_index, Aggregate, Error, Select};
+use crate::query::QueryOptions;
+use crate::{ArrayRef, ChunkedArray, Field, View};
+use std::collections::HashMap;
+
+/// Split the rows of the view into groups which share the same values in the key columns, nulls
+/// are grouped together. The groups are ordered by their first row, without any keys every row
+/// belongs to a single group.
+pub fn group_indices(view: &View, keys: &[usize]) -> Result<Vec<Vec<usize>>, crate::Error> {
+    if keys.is_empty() {
+        return Ok(vec![(0..view.num_rows()).collect()]);
+    }
+
+    let columns: Vec<&ChunkedArray> = keys.iter().map(|i| &view.columns()[*i]).collect();
+
+    let mut lookup: HashMap<Vec<u8>, usize> = HashMap::new();
+    let mut groups: Vec<Vec<usize>> = vec![];
+    let mut row = 0;
+
+    // the keys are hashed a chunk at a time so the key columns are never concatenated
+    for piece in align_chunks(&columns) {
+        for key in row_keys(&piece)? {
+            let group = *lookup.entry(key).or_insert_with(|| {
+                groups.push(vec![]);
+                groups.len() - 1
+            });
+            groups[group].push(row);
+            row += 1;
+        }
+    }
+
+    Ok(groups)
+}
+
+/// Produce one row per distinct combination of the key columns. Every selection must either be an
+/// aggregate or only reference the key columns, an empty selection returns the key columns.
+pub fn group_by(
+    view: &View,
+    keys: Vec<Column>,
+    columns: Vec<Select>,
+) -> Result<View, crate::Error> {
+    group_by_with_options(view, keys, columns, &QueryOptions::default())
+}
+
+/// Group the rows, the options are passed along to the selections of the key columns
+pub fn group_by_with_options(
+    view: &View,
+    keys: Vec<Column>,
+    columns: Vec<Select>,
+    options: &QueryOptions,
+) -> Result<View, crate::Error> {
+    let keys = keys
+        .into_iter()
+        .map(|column| {
+            view.get_index(&column).ok_or_else(|| {
+                Error::InvalidColumn {
+                    column: Select::Column(column),
+                }
+                .into()
+            })
+        })
+        .collect::<Result<Vec<usize>, crate::Error>>()?;
+
+    let groups = group_indices(view, &keys)?;
+
+    // the key columns hold the same values for every row of a group, so the first row of each
+    // group can stand in for the group
+    let first_rows: Vec<usize> = groups
+        .iter()
+        .filter_map(|group| group.first().copied())
+        .collect();
+
+    let first_view = View::new(
+        view.fields().clone(),
+        view.columns()
+            .iter()
+            .map(|arr| take_rows(&arr.to_array()?, &first_rows))
+            .collect::<Result<Vec<_>, crate::Error>>()?,
+    );
+
+    let columns = if columns.is_empty() {
+        keys.iter()
+            .map(|i| Select::Column(Column::Position(*i)))
+            .collect()
+    } else {
+        columns
+    };
+
+    let mut fields: Vec<Field> = Vec::with_capacity(columns.len());
+    let mut data: Vec<ArrayRef> = Vec::with_capacity(columns.len());
+
+    for sel in columns.iter() {
+        let (field, array_ref) = select_grouped(view, &first_view, &keys, &groups, sel, options)?;
+        fields.push(field);
+        data.push(array_ref);
+    }
+
+    Ok(View::new(fields, data))
+}
+
+fn select_grouped(
+    view: &View,
+    first_view: &View,
+    keys: &[usize],
+    groups: &[Vec<usize>],
+    s: &Select,
+    options: &QueryOptions,
+) -> Result<(Field, ArrayRef), crate::Error> {
+    match s {
+        Select::Aggregate(agg, column) => aggregate_column(view, agg, column, groups),
+        Select::Alias(sel, alias) => {
+            let (field, array_ref) = select_grouped(view, first_view, keys, groups, sel, options)?;
+            let field = Field::new(
+                alias.as_str(),
+                field.data_type().clone(),
+                field.is_nullable(),
+            );
+            Ok((field, array_ref))
+        }
+        _ => {
+            let is_grouped = s.columns().iter().all(|column| {
+                view.get_index(column)
+                    .map(|i| keys.contains(&i))
+                    .unwrap_or(false)
+            });
+
+            if !is_grouped {
+                return Err(Error::UngroupedColumn { column: s.clone() }.into());
+            }
+
+            select_index(first_view, s, options)
+        }
+    }
+}
+
+/// Aggregate each group of rows of the column, the field is named after the aggregate e.g.
+/// `sum(price)`
+pub(crate) fn aggregate_column(
+    view: &View,
+    agg: &Aggregate,
+    column: &Column,
+    groups: &[Vec<usize>],
+) -> Result<(Field, ArrayRef), crate::Error> {
+    let (field, arr) = view
+        .chunked_subview(column)
+        .ok_or_else(|| Error::InvalidColumn {
+            column: Select::Aggregate(agg.clone(), column.clone()),
+        })?;
+    let arr = arr.to_array()?;
+
+    let array_ref = aggregate(&arr, agg, groups)?;
+
+    let field = Field::new(
+        &format!("{}({})", agg.as_str(), field.name()),
+        array_ref.data_type().clone(),
+        *agg != Aggregate::Count,
+    );
+
+    Ok((field, array_ref))
+}
+
+#[cfg(test)]
+mod test_group {
+    use super::*;
+    use crate::{as_array, col, sel};
+    use arrow::array::{BinaryArray, Float64Array, Int64Array, UInt64Array, UInt8Array};
+    use arrow::datatypes::DataType;
+    use std::sync::Arc;
+
+    fn sample_view() -> View {
+        View::new(
+            vec![
+                Field::new("team", DataType::Utf8, false),
+                Field::new("points", DataType::UInt8, true),
+                Field::new("delta", DataType::Int64, false),
+            ],
+            vec![
+                Arc::new(BinaryArray::from(vec!["a", "b", "a", "c", "b"])) as ArrayRef,
+                Arc::new(UInt8Array::from(vec![
+                    Some(1),
+                    Some(2),
+                    Some(3),
+                    None,
+                    Some(4),
+                ])) as ArrayRef,
+                Arc::new(Int64Array::from(vec![-1, 2, -3, 4, -5])) as ArrayRef,
+            ],
+        )
+    }
+
+    #[test]
+    fn it_groups_chunked_keys() {
+        let mut view = sample_view();
+        view.append(&sample_view()).unwrap();
+
+        let groups = group_indices(&view, &[0, 2]).unwrap();
+        assert_eq!(
+            groups,
+            vec![vec![0, 5], vec![1, 6], vec![2, 7], vec![3, 8], vec![4, 9]]
+        );
+    }
+
+    #[test]
+    fn it_groups_by_a_utf8_key() {
+        let view = group_by(
+            &sample_view(),
+            vec![col!("team")],
+            vec![
+                sel!("team"),
+                sel!((Aggregate::Sum, "points")),
+                sel!((Aggregate::Count, "points"), "n"),
+                sel!((Aggregate::Avg, "points")),
+                sel!((Aggregate::Min, "delta")),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(view.num_rows(), 3);
+        assert_eq!(view.field(&col!(1)).unwrap().name(), "sum(points)");
+
+        let team = view.column(&col!("team")).unwrap();
+        let team = as_array!(team, BinaryArray).unwrap();
+        let teams: Vec<&[u8]> = (0..team.len()).map(|i| team.value(i)).collect();
+        assert_eq!(teams, vec![b"a" as &[u8], b"b" as &[u8], b"c" as &[u8]]);
+
+        let sum = view.column(&col!("sum(points)")).unwrap();
+        let expected_sum = UInt64Array::from(vec![Some(4), Some(6), None]);
+        assert_eq!(as_array!(sum, UInt64Array).unwrap(), &expected_sum);
+
+        let count = view.column(&col!("n")).unwrap();
+        let expected_count = UInt64Array::from(vec![2, 2, 0]);
+        assert_eq!(as_array!(count, UInt64Array).unwrap(), &expected_count);
+
+        let avg = view.column(&col!("avg(points)")).unwrap();
+        let expected_avg = Float64Array::from(vec![Some(2.0), Some(3.0), None]);
+        assert_eq!(as_array!(avg, Float64Array).unwrap(), &expected_avg);
+
+        let min = view.column(&col!("min(delta)")).unwrap();
+        let expected_min = Int64Array::from(vec![-3, -5, 4]);
+        assert_eq!(as_array!(min, Int64Array).unwrap(), &expected_min);
+    }
+
+    #[test]
+    fn it_rejects_ungrouped_columns() {
+        let res = group_by(&sample_view(), vec![col!("team")], vec![sel!("points")]);
+        assert!(res.is_err());
+    }
+}
@@ -0,0 +1,353 @@
+use crate::column::Column;
+use crate::datatype::{is_lossy_promotion, promote, promote_arrays};
+use crate::ops::hash::row_keys;
+use crate::ops::take::take;
+use crate::{ArrayRef, DataType, Field, View};
+use arrow::array::Array;
+use snafu::Snafu;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("The column does not exist {}", column))]
+    InvalidColumn { column: Column },
+
+    #[snafu(display(
+        "The join keys must share a data type or promote to one without rounding. {:?} != {:?}",
+        lhs,
+        rhs
+    ))]
+    UnequalKeyTypes { lhs: DataType, rhs: DataType },
+
+    #[snafu(display("A join requires at least one pair of key columns"))]
+    MissingKeys,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JoinType {
+    Inner,
+    Left,
+    Right,
+    Full,
+    /// The rows of the left view which have a match, only the left columns are kept
+    Semi,
+    /// The rows of the left view which do not have a match, only the left columns are kept
+    Anti,
+}
+
+/// Appended to the names of the fields which exist on both sides of the join
+pub const DEFAULT_SUFFIXES: (&str, &str) = ("_left", "_right");
+
+/// Hash join the views on the pairs of (left, right) key columns. Null keys never match, the
+/// fields which exist on both sides are renamed with the `DEFAULT_SUFFIXES`.
+pub fn join(
+    left: &View,
+    right: &View,
+    on: Vec<(Column, Column)>,
+    join_type: JoinType,
+) -> Result<View, crate::Error> {
+    join_with_suffixes(left, right, on, join_type, DEFAULT_SUFFIXES)
+}
+
+pub fn join_with_suffixes(
+    left: &View,
+    right: &View,
+    on: Vec<(Column, Column)>,
+    join_type: JoinType,
+    suffixes: (&str, &str),
+) -> Result<View, crate::Error> {
+    if on.is_empty() {
+        return Err(Error::MissingKeys.into());
+    }
+
+    let mut left_keys: Vec<ArrayRef> = Vec::with_capacity(on.len());
+    let mut right_keys: Vec<ArrayRef> = Vec::with_capacity(on.len());
+
+    for (lhs, rhs) in on {
+        let lhs_arr = left
+            .chunked_column(&lhs)
+            .ok_or(Error::InvalidColumn { column: lhs })?
+            .to_array()?;
+        let rhs_arr = right
+            .chunked_column(&rhs)
+            .ok_or(Error::InvalidColumn { column: rhs })?
+            .to_array()?;
+
+        // a rounded key would pair up distinct values, so only exact promotions are joined
+        if lhs_arr.data_type() != rhs_arr.data_type()
+            && (promote(lhs_arr.data_type(), rhs_arr.data_type()).is_none()
+                || is_lossy_promotion(lhs_arr.data_type(), rhs_arr.data_type()))
+        {
+            return Err(Error::UnequalKeyTypes {
+                lhs: lhs_arr.data_type().clone(),
+                rhs: rhs_arr.data_type().clone(),
+            }
+            .into());
+        }
+
+        // the keys are hashed by their bytes, so they must share a data type
+        let (lhs_arr, rhs_arr) = promote_arrays(&lhs_arr, &rhs_arr)?;
+
+        left_keys.push(lhs_arr);
+        right_keys.push(rhs_arr);
+    }
+
+    let (left_indices, right_indices) = join_indices(&left_keys, &right_keys, join_type)?;
+
+    let mut fields: Vec<Field> = vec![];
+    let mut columns: Vec<ArrayRef> = vec![];
+
+    let right_names: HashSet<&String> = right.fields().iter().map(|f| f.name()).collect();
+    let left_names: HashSet<&String> = left.fields().iter().map(|f| f.name()).collect();
+
+    let is_semi = join_type == JoinType::Semi || join_type == JoinType::Anti;
+
+    for (field, arr) in left.fields().iter().zip(left.columns()) {
+        let name = if !is_semi && right_names.contains(field.name()) {
+            format!("{}{}", field.name(), suffixes.0)
+        } else {
+            field.name().clone()
+        };
+        let nullable =
+            field.is_nullable() || join_type == JoinType::Right || join_type == JoinType::Full;
+
+        fields.push(Field::new(&name, field.data_type().clone(), nullable));
+        columns.push(take(&arr.to_array()?, &left_indices)?);
+    }
+
+    if !is_semi {
+        for (field, arr) in right.fields().iter().zip(right.columns()) {
+            let name = if left_names.contains(field.name()) {
+                format!("{}{}", field.name(), suffixes.1)
+            } else {
+                field.name().clone()
+            };
+            let nullable =
+                field.is_nullable() || join_type == JoinType::Left || join_type == JoinType::Full;
+
+            fields.push(Field::new(&name, field.data_type().clone(), nullable));
+            columns.push(take(&arr.to_array()?, &right_indices)?);
+        }
+    }
+
+    Ok(View::new(fields, columns))
+}
+
+fn has_null(columns: &[ArrayRef], row: usize) -> bool {
+    columns.iter().any(|arr| arr.is_null(row))
+}
+
+/// Pair up the rows of the left and right keys, a `None` is a row without a match on that side
+fn join_indices(
+    left_keys: &[ArrayRef],
+    right_keys: &[ArrayRef],
+    join_type: JoinType,
+) -> Result<(Vec<Option<usize>>, Vec<Option<usize>>), crate::Error> {
+    let left_rows = row_keys(left_keys)?;
+    let right_rows = row_keys(right_keys)?;
+
+    // build the table from the right side and probe it with the left
+    let mut table: HashMap<&[u8], Vec<usize>> = HashMap::new();
+
+    for (i, key) in right_rows.iter().enumerate() {
+        if !has_null(right_keys, i) {
+            table.entry(key.as_slice()).or_insert_with(Vec::new).push(i);
+        }
+    }
+
+    let mut left_indices: Vec<Option<usize>> = vec![];
+    let mut right_indices: Vec<Option<usize>> = vec![];
+    let mut right_matched = vec![false; right_rows.len()];
+
+    for (i, key) in left_rows.iter().enumerate() {
+        let matches = if has_null(left_keys, i) {
+            None
+        } else {
+            table.get(key.as_slice())
+        };
+
+        match (join_type, matches) {
+            (JoinType::Semi, Some(_)) | (JoinType::Anti, None) => left_indices.push(Some(i)),
+            (JoinType::Semi, None) | (JoinType::Anti, Some(_)) => {}
+            (_, Some(rows)) => {
+                for row in rows {
+                    left_indices.push(Some(i));
+                    right_indices.push(Some(*row));
+                    right_matched[*row] = true;
+                }
+            }
+            (JoinType::Left, None) | (JoinType::Full, None) => {
+                left_indices.push(Some(i));
+                right_indices.push(None);
+            }
+            (_, None) => {}
+        }
+    }
+
+    if join_type == JoinType::Right || join_type == JoinType::Full {
+        for (row, matched) in right_matched.iter().enumerate() {
+            if !matched {
+                left_indices.push(None);
+                right_indices.push(Some(row));
+            }
+        }
+    }
+
+    Ok((left_indices, right_indices))
+}
+
+#[cfg(test)]
+mod test_join {
+    use super::*;
+    use crate::{as_array, col};
+    use arrow::array::{BinaryArray, UInt32Array, UInt8Array};
+    use std::sync::Arc;
+
+    fn left_view() -> View {
+        View::new(
+            vec![
+                Field::new("id", DataType::UInt8, true),
+                Field::new("region", DataType::Utf8, false),
+                Field::new("value", DataType::UInt32, false),
+            ],
+            vec![
+                Arc::new(UInt8Array::from(vec![Some(1), Some(2), Some(3), None])) as ArrayRef,
+                Arc::new(BinaryArray::from(vec!["eu", "us", "eu", "us"])) as ArrayRef,
+                Arc::new(UInt32Array::from(vec![10, 20, 30, 40])) as ArrayRef,
+            ],
+        )
+    }
+
+    fn right_view() -> View {
+        View::new(
+            vec![
+                Field::new("id", DataType::UInt8, true),
+                Field::new("region", DataType::Utf8, false),
+                Field::new("value", DataType::UInt32, false),
+            ],
+            vec![
+                Arc::new(UInt8Array::from(vec![
+                    Some(2),
+                    Some(1),
+                    Some(1),
+                    None,
+                    Some(4),
+                ])) as ArrayRef,
+                Arc::new(BinaryArray::from(vec!["us", "eu", "us", "us", "eu"])) as ArrayRef,
+                Arc::new(UInt32Array::from(vec![1, 2, 3, 4, 5])) as ArrayRef,
+            ],
+        )
+    }
+
+    fn values(view: &View, column: &str) -> Vec<Option<u32>> {
+        let arr = view.column(&col!(column)).unwrap();
+        let arr = as_array!(arr, UInt32Array).unwrap();
+        (0..arr.len())
+            .map(|i| {
+                if arr.is_null(i) {
+                    None
+                } else {
+                    Some(arr.value(i))
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn it_inner_joins() {
+        let view = join(
+            &left_view(),
+            &right_view(),
+            vec![(col!("id"), col!("id"))],
+            JoinType::Inner,
+        )
+        .unwrap();
+
+        assert_eq!(view.num_columns(), 6);
+        assert_eq!(view.field(&col!(0)).unwrap().name(), "id_left");
+        assert_eq!(view.field(&col!(3)).unwrap().name(), "id_right");
+
+        assert_eq!(
+            values(&view, "value_left"),
+            vec![Some(10), Some(10), Some(20)]
+        );
+        assert_eq!(
+            values(&view, "value_right"),
+            vec![Some(2), Some(3), Some(1)]
+        );
+    }
+
+    #[test]
+    fn it_joins_on_multiple_columns() {
+        let view = join(
+            &left_view(),
+            &right_view(),
+            vec![(col!("id"), col!("id")), (col!("region"), col!("region"))],
+            JoinType::Left,
+        )
+        .unwrap();
+
+        assert_eq!(
+            values(&view, "value_left"),
+            vec![Some(10), Some(20), Some(30), Some(40)]
+        );
+        assert_eq!(
+            values(&view, "value_right"),
+            vec![Some(2), Some(1), None, None]
+        );
+    }
+
+    #[test]
+    fn it_full_joins() {
+        let view = join(
+            &left_view(),
+            &right_view(),
+            vec![(col!("id"), col!("id"))],
+            JoinType::Full,
+        )
+        .unwrap();
+
+        assert_eq!(
+            values(&view, "value_left"),
+            vec![Some(10), Some(10), Some(20), Some(30), Some(40), None, None]
+        );
+        assert_eq!(
+            values(&view, "value_right"),
+            vec![Some(2), Some(3), Some(1), None, None, Some(4), Some(5)]
+        );
+    }
+
+    #[test]
+    fn it_semi_and_anti_joins() {
+        let on = || vec![(col!("id"), col!("id"))];
+
+        let semi = join(&left_view(), &right_view(), on(), JoinType::Semi).unwrap();
+        assert_eq!(semi.num_columns(), 3);
+        assert_eq!(values(&semi, "value"), vec![Some(10), Some(20)]);
+
+        let anti = join(&left_view(), &right_view(), on(), JoinType::Anti).unwrap();
+        assert_eq!(values(&anti, "value"), vec![Some(30), Some(40)]);
+    }
+
+    #[test]
+    fn it_rejects_keys_which_would_be_rounded() {
+        let left = crate::view!(["id", arrow::datatypes::UInt64Type, [9_007_199_254_740_993]]);
+        let right = crate::view!(["id", arrow::datatypes::Int64Type, [9_007_199_254_740_992]]);
+
+        let res = join(
+            &left,
+            &right,
+            vec![(col!("id"), col!("id"))],
+            JoinType::Inner,
+        );
+        match res {
+            Err(crate::Error::JoinError {
+                source: Error::UnequalKeyTypes { .. },
+            }) => {}
+            res => panic!(
+                "expected the keys to be rejected, got {:?}",
+                res.map(|_| ())
+            ),
+        }
+    }
+}
@@ -1,14 +1,31 @@
 pub mod filter;
+pub mod group;
+pub mod join;
 pub mod select;
 
+use crate::column::Column;
+use crate::ops::cmp::NanOrdering;
+use crate::ops::math::DivideByZero;
+use crate::ops::sort::{self, Nulls, Sort};
 use crate::{Error, View};
 use filter::Filter;
 use select::Select;
 
+/// Settings which change how the query evaluates its selections and filters
+#[derive(Clone, Debug, Default)]
+pub struct QueryOptions {
+    pub divide_by_zero: DivideByZero,
+    /// The ordering of NaN in comparisons, filters and sorting
+    pub nans: NanOrdering,
+}
+
 pub struct Query<'a> {
     view: View,
     select: Vec<Select>,
     filter: Option<Filter<'a>>,
+    group_by: Vec<Column>,
+    order_by: Vec<(Column, Sort, Nulls)>,
+    options: QueryOptions,
 }
 
 impl<'a> Query<'a> {
@@ -17,6 +34,9 @@ impl<'a> Query<'a> {
             view,
             select: vec![],
             filter: None,
+            group_by: vec![],
+            order_by: vec![],
+            options: QueryOptions::default(),
         }
     }
 
@@ -30,9 +50,165 @@ impl<'a> Query<'a> {
         self
     }
 
+    /// Group the rows by the columns, the selections must then be aggregates or group by columns
+    pub fn group_by(mut self, group_by: Vec<Column>) -> Query<'a> {
+        self.group_by = group_by;
+        self
+    }
+
+    /// Order the rows by the columns of the source view, or by the output columns when the query
+    /// is grouped. Nulls are placed according to `Sort::default_nulls`.
+    pub fn order_by(mut self, order_by: Vec<(Column, Sort)>) -> Query<'a> {
+        self.order_by = order_by
+            .into_iter()
+            .map(|(column, sort)| (column, sort, sort.default_nulls()))
+            .collect();
+        self
+    }
+
+    /// Order the rows with an explicit placement of the nulls
+    pub fn order_by_nulls(mut self, order_by: Vec<(Column, Sort, Nulls)>) -> Query<'a> {
+        self.order_by = order_by;
+        self
+    }
+
+    /// Whether a division by zero fails the query or produces a null, it fails by default
+    pub fn divide_by_zero(mut self, divide_by_zero: DivideByZero) -> Query<'a> {
+        self.options.divide_by_zero = divide_by_zero;
+        self
+    }
+
+    /// Whether NaN is greater than every other value or behaves as a null, it is the greatest by
+    /// default
+    pub fn nans(mut self, nans: NanOrdering) -> Query<'a> {
+        self.options.nans = nans;
+        self
+    }
+
     pub fn exec(self) -> Result<View, Error> {
-        let view = select::select(self.view, self.select)?;
+        // the filter runs against the full view so that it can reference columns which the select
+        // does not project
+        let view = match self.filter {
+            Some(f) => filter::filter_with_options(&self.view, f, &self.options)?,
+            None => self.view,
+        };
+
+        // grouping reduces the rows, so the ordering can only be applied to its output
+        if !self.group_by.is_empty() || self.select.iter().any(|s| s.is_aggregate()) {
+            let view =
+                group::group_by_with_options(&view, self.group_by, self.select, &self.options)?;
+            return order(view, self.order_by, &self.options);
+        }
+
+        let view = order(view, self.order_by, &self.options)?;
+
+        // an empty select keeps every column
+        if self.select.is_empty() {
+            return Ok(view);
+        }
+
+        let view = select::select_with_options(view, self.select, &self.options)?;
 
         Ok(view)
     }
 }
+
+fn order(
+    view: View,
+    order_by: Vec<(Column, Sort, Nulls)>,
+    options: &QueryOptions,
+) -> Result<View, Error> {
+    if order_by.is_empty() {
+        return Ok(view);
+    }
+    sort::sort_with_nans(&view, order_by, options.nans)
+}
+
+#[cfg(test)]
+mod test_query {
+    use super::*;
+    use crate::datatype::ScalarValue;
+    use crate::ops::cmp::CmpOp;
+    use crate::query::select::Aggregate;
+    use crate::{array, as_array, col, columns, sel, view};
+    use arrow::array::{PrimitiveArray, UInt8Array};
+    use arrow::datatypes as dt;
+
+    #[test]
+    fn it_filters_on_a_column_that_is_not_selected() {
+        let view = view!(
+            ["a", dt::UInt8Type, [1, 2, 3, 4, 5]],
+            ["b", dt::UInt8Type, [5, 4, 3, 2, 1]]
+        );
+
+        let res_view = Query::new(view)
+            .select(vec![sel!("a")])
+            .filter(Filter::Scalar("b".into(), CmpOp::Gt, ScalarValue::UInt8(2)))
+            .exec()
+            .unwrap();
+
+        assert_eq!(res_view.num_columns(), 1);
+
+        let a = res_view.column(&col!("a")).unwrap();
+        let expected_a = array!(dt::UInt8Type, [1, 2, 3]);
+        assert_eq!(as_array!(a, UInt8Array).unwrap(), &expected_a);
+    }
+
+    #[test]
+    fn it_keeps_all_columns_without_a_select() {
+        let view = view!(
+            ["a", dt::UInt8Type, [1, 2, 3, 4, 5]],
+            ["b", dt::UInt8Type, [5, 4, 3, 2, 1]]
+        );
+
+        let res_view = Query::new(view)
+            .filter(Filter::Columns("a".into(), CmpOp::Lt, "b".into()))
+            .exec()
+            .unwrap();
+
+        assert_eq!(res_view.num_columns(), 2);
+        assert_eq!(res_view.num_rows(), 2);
+    }
+
+    #[test]
+    fn it_orders_before_selecting() {
+        let view = view!(
+            ["a", dt::UInt8Type, [1, 2, 3, 4, 5]],
+            ["b", dt::UInt8Type, [3, 1, 3, 2, 1]]
+        );
+
+        let res_view = Query::new(view)
+            .select(vec![sel!("a")])
+            .order_by(
+                columns!("b", "a")
+                    .into_iter()
+                    .zip(vec![Sort::ASC, Sort::DESC])
+                    .collect(),
+            )
+            .exec()
+            .unwrap();
+
+        let a = res_view.column(&col!("a")).unwrap();
+        let expected_a = array!(dt::UInt8Type, [5, 2, 4, 3, 1]);
+        assert_eq!(as_array!(a, UInt8Array).unwrap(), &expected_a);
+    }
+
+    #[test]
+    fn it_orders_the_grouped_output() {
+        let view = view!(
+            ["a", dt::UInt8Type, [1, 2, 1, 2, 3]],
+            ["b", dt::UInt8Type, [1, 1, 1, 5, 1]]
+        );
+
+        let res_view = Query::new(view)
+            .select(vec![sel!("a"), sel!((Aggregate::Sum, "b"), "total")])
+            .group_by(columns!("a"))
+            .order_by(vec![(col!("total"), Sort::DESC)])
+            .exec()
+            .unwrap();
+
+        let a = res_view.column(&col!("a")).unwrap();
+        let expected_a = array!(dt::UInt8Type, [2, 1, 3]);
+        assert_eq!(as_array!(a, UInt8Array).unwrap(), &expected_a);
+    }
+}
@@ -1,7 +1,8 @@
 use crate::column::Column;
-use crate::{as_array, ops::math, utils, ArrayRef, DataType, Field, View};
-use arrow::array::PrimitiveArray;
-use arrow::datatypes as dt;
+use crate::datatype::{promote, ScalarValue};
+use crate::expr::{evaluate_with_options, Expr};
+use crate::query::{group, QueryOptions};
+use crate::{ops::math, utils, ArrayRef, ChunkedArray, DataType, Field, View};
 use snafu::Snafu;
 
 #[derive(Debug, Snafu)]
@@ -16,14 +17,25 @@ pub enum Error {
     ))]
     InvalidArithmeticDataType { op: Arithmetic, data_type: DataType },
 
-    #[snafu(display("The data types must be the same. {:?} != {:?}", lhs, rhs))]
+    #[snafu(display(
+        "The data types must be the same or promotable to a common type. {:?} != {:?}",
+        lhs,
+        rhs
+    ))]
     UnequalDataTypes { lhs: DataType, rhs: DataType },
 
     ArithmeticError {
         source: crate::error::arrow_error::ArrowError,
     },
+
+    #[snafu(display(
+        "The column must be aggregated or be one of the group by columns {}",
+        column
+    ))]
+    UngroupedColumn { column: Select },
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub enum Aggregate {
     Min,
     Max,
@@ -32,13 +44,28 @@ pub enum Aggregate {
     Count,
 }
 
+impl Aggregate {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Min => "min",
+            Self::Max => "max",
+            Self::Sum => "sum",
+            Self::Avg => "avg",
+            Self::Count => "count",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum Arithmetic {
     Add,
     Sub,
     Div,
     Mul,
-    // Mod,
+    Mod,
+    /// Division which truncates the result toward zero
+    IntDiv,
+    Pow,
 }
 
 impl Arithmetic {
@@ -48,7 +75,24 @@ impl Arithmetic {
             Self::Sub => "-",
             Self::Div => "/",
             Self::Mul => "*",
-            // Self::Mod => "%",
+            Self::Mod => "%",
+            Self::IntDiv => "//",
+            Self::Pow => "^",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Unary {
+    Neg,
+    Abs,
+}
+
+impl Unary {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Neg => "neg",
+            Self::Abs => "abs",
         }
     }
 }
@@ -63,7 +107,38 @@ pub enum Select {
     Column(Column),
     Alias(Box<Select>, String),
     Arithmetic(Arithmetic, Column, Column),
-    //Aggregate(Column)
+    /// Arithmetic between a column and a literal, e.g. `price * 1.08`
+    ArithmeticScalar(Arithmetic, Column, ScalarValue<'static>),
+    /// Arithmetic between a literal and a column, e.g. `100 - discount`
+    ScalarArithmetic(Arithmetic, ScalarValue<'static>, Column),
+    /// A unary operation on a column, e.g. `abs(delta)`
+    Unary(Unary, Column),
+    Aggregate(Aggregate, Column),
+    Expr(Expr<'static>),
+}
+
+impl Select {
+    /// The columns of the view which are referenced by the selection
+    pub fn columns(&self) -> Vec<&Column> {
+        match self {
+            Select::Column(column) => vec![column],
+            Select::Alias(sel, _) => sel.columns(),
+            Select::Arithmetic(_, lhs, rhs) => vec![lhs, rhs],
+            Select::ArithmeticScalar(_, column, _) => vec![column],
+            Select::ScalarArithmetic(_, _, column) => vec![column],
+            Select::Unary(_, column) => vec![column],
+            Select::Aggregate(_, column) => vec![column],
+            Select::Expr(expr) => expr.columns(),
+        }
+    }
+
+    pub fn is_aggregate(&self) -> bool {
+        match self {
+            Select::Alias(sel, _) => sel.is_aggregate(),
+            Select::Aggregate(_, _) => true,
+            _ => false,
+        }
+    }
 }
 
 impl std::fmt::Display for Select {
@@ -72,6 +147,15 @@ impl std::fmt::Display for Select {
             Select::Column(column) => write!(fmt, "{}", column),
             Select::Alias(sel, name) => write!(fmt, "Alias({} as {})", sel, name),
             Select::Arithmetic(op, lhs, rhs) => write!(fmt, "{} {} {}", lhs, op.as_str(), rhs),
+            Select::ArithmeticScalar(op, lhs, rhs) => {
+                write!(fmt, "{} {} {}", lhs, op.as_str(), rhs)
+            }
+            Select::ScalarArithmetic(op, lhs, rhs) => {
+                write!(fmt, "{} {} {}", lhs, op.as_str(), rhs)
+            }
+            Select::Unary(op, column) => write!(fmt, "{}({})", op.as_str(), column),
+            Select::Aggregate(agg, column) => write!(fmt, "{}({})", agg.as_str(), column),
+            Select::Expr(expr) => write!(fmt, "{}", expr),
         }
     }
 }
@@ -100,7 +184,47 @@ impl From<(Arithmetic, &str, &str)> for Select {
     }
 }
 
+impl From<(Arithmetic, &str, ScalarValue<'static>)> for Select {
+    fn from(s: (Arithmetic, &str, ScalarValue<'static>)) -> Select {
+        Select::ArithmeticScalar(s.0, s.1.into(), s.2)
+    }
+}
+
+impl From<(Arithmetic, ScalarValue<'static>, &str)> for Select {
+    fn from(s: (Arithmetic, ScalarValue<'static>, &str)) -> Select {
+        Select::ScalarArithmetic(s.0, s.1, s.2.into())
+    }
+}
+
+impl From<(Unary, &str)> for Select {
+    fn from(s: (Unary, &str)) -> Select {
+        Select::Unary(s.0, s.1.into())
+    }
+}
+
+impl From<Expr<'static>> for Select {
+    fn from(s: Expr<'static>) -> Select {
+        Select::Expr(s)
+    }
+}
+
+impl From<(Aggregate, &str)> for Select {
+    fn from(s: (Aggregate, &str)) -> Select {
+        Select::Aggregate(s.0, s.1.into())
+    }
+}
+
 pub fn select(view: View, columns: Vec<Select>) -> Result<View, crate::Error> {
+    select_with_options(view, columns, &QueryOptions::default())
+}
+
+/// Select the columns, the options decide how the arithmetic handles edge cases such as a
+/// division by zero
+pub fn select_with_options(
+    view: View,
+    columns: Vec<Select>,
+    options: &QueryOptions,
+) -> Result<View, crate::Error> {
     // check for existance of the columns on the df
     if let Some(column) = columns.iter().find(|s| !column_exists(&view, s)) {
         return Err(Error::InvalidColumn {
@@ -109,18 +233,50 @@ pub fn select(view: View, columns: Vec<Select>) -> Result<View, crate::Error> {
         .into());
     }
 
+    // aggregates reduce the rows, so the whole view is treated as a single group
+    if columns.iter().any(|s| s.is_aggregate()) {
+        return group::group_by_with_options(&view, vec![], columns, options);
+    }
+
     let mut fields: Vec<Field> = Vec::with_capacity(columns.len());
-    let mut data: Vec<ArrayRef> = Vec::with_capacity(columns.len());
+    let mut data: Vec<ChunkedArray> = Vec::with_capacity(columns.len());
 
     for sel in columns.iter() {
-        let (field, array_ref) = select_index(&view, sel)?;
+        let (field, array_ref) = select_chunked(&view, sel, options)?;
         // fields get a new numeric index after a select, the numeric index relates to the position
         // within the columns arg
         fields.push(field);
         data.push(array_ref);
     }
 
-    Ok(View::new(fields, data))
+    Ok(View::from_chunks(fields, data))
+}
+
+/// Columns which are selected as they are keep their chunks, anything computed from the values
+/// produces a single array
+fn select_chunked(
+    view: &View,
+    s: &Select,
+    options: &QueryOptions,
+) -> Result<(Field, ChunkedArray), crate::Error> {
+    match s {
+        Select::Column(column) => view
+            .chunked_subview(&column)
+            .ok_or_else(|| Error::InvalidColumn { column: s.clone() }.into()),
+        Select::Alias(sel, alias) => {
+            let (field, arr) = select_chunked(&view, sel, options)?;
+            let field = Field::new(
+                alias.as_str(),
+                field.data_type().clone(),
+                field.is_nullable(),
+            );
+            Ok((field, arr))
+        }
+        _ => {
+            let (field, arr) = select_index(view, s, options)?;
+            Ok((field, ChunkedArray::from(arr)))
+        }
+    }
 }
 
 fn column_exists(view: &View, s: &Select) -> bool {
@@ -128,16 +284,28 @@ fn column_exists(view: &View, s: &Select) -> bool {
         Select::Column(column) => view.index_exists(&column),
         Select::Alias(sel, _) => column_exists(&view, sel),
         Select::Arithmetic(_, lhs, rhs) => view.index_exists(&lhs) && view.index_exists(&rhs),
+        Select::ArithmeticScalar(_, column, _) => view.index_exists(&column),
+        Select::ScalarArithmetic(_, _, column) => view.index_exists(&column),
+        Select::Unary(_, column) => view.index_exists(&column),
+        Select::Aggregate(_, column) => view.index_exists(&column),
+        Select::Expr(expr) => expr.columns().iter().all(|c| view.index_exists(c)),
     }
 }
 
-fn select_index(view: &View, s: &Select) -> Result<(Field, ArrayRef), crate::Error> {
+pub(crate) fn select_index(
+    view: &View,
+    s: &Select,
+    options: &QueryOptions,
+) -> Result<(Field, ArrayRef), crate::Error> {
     match s {
-        Select::Column(column) => view
-            .subview(&column)
-            .ok_or_else(|| Error::InvalidColumn { column: s.clone() }.into()),
+        Select::Column(column) => {
+            let (field, arr) = view
+                .chunked_subview(&column)
+                .ok_or_else(|| Error::InvalidColumn { column: s.clone() })?;
+            Ok((field, arr.to_array()?))
+        }
         Select::Alias(sel, alias) => {
-            if let Ok((field, array_ref)) = select_index(&view, sel) {
+            if let Ok((field, array_ref)) = select_index(&view, sel, options) {
                 // create the field with the new name -- arrow doesn't expose a set_name method and
                 // the name prop is private
                 let field = Field::new(
@@ -149,30 +317,35 @@ fn select_index(view: &View, s: &Select) -> Result<(Field, ArrayRef), crate::Err
             }
             Err(Error::InvalidColumn { column: s.clone() }.into())
         }
-        Select::Arithmetic(op, lhs, rhs) => apply_arithmetic(view, op, lhs, rhs),
+        Select::Arithmetic(op, lhs, rhs) => apply_arithmetic(view, op, lhs, rhs, options),
+        Select::ArithmeticScalar(op, column, value) => {
+            apply_scalar_arithmetic(view, op, column, value, false, options)
+        }
+        Select::ScalarArithmetic(op, value, column) => {
+            apply_scalar_arithmetic(view, op, column, value, true, options)
+        }
+        Select::Unary(op, column) => apply_unary(view, op, column),
+        Select::Aggregate(agg, column) => {
+            let groups = vec![(0..view.num_rows()).collect()];
+            group::aggregate_column(view, agg, column, &groups)
+        }
+        Select::Expr(expr) => {
+            let array_ref = evaluate_with_options(view, expr, options)?;
+            let field = Field::new(&expr.to_string(), array_ref.data_type().clone(), true);
+            Ok((field, array_ref))
+        }
     }
 }
 
-macro_rules! apply_arithmetic {
-    ($lhs:ident, $rhs:ident, $op:ident, $([$dt:path, $ty:ty]),*) => {
-
-        match $lhs.data_type() {
-            $(
-                $dt => {
-                    let lhs_arr = as_array!($lhs, PrimitiveArray<$ty>).unwrap();
-                    let rhs_arr = as_array!($rhs, PrimitiveArray<$ty>).unwrap();
+/// The field and the values of the column, chunked columns are concatenated
+fn column_values(view: &View, column: &Column) -> Result<(Field, ArrayRef), crate::Error> {
+    let (field, arr) = view
+        .chunked_subview(column)
+        .ok_or_else(|| Error::InvalidColumn {
+            column: Select::Column(column.clone()),
+        })?;
 
-                    (match $op {
-                        Arithmetic::Add => math::add(lhs_arr, rhs_arr),
-                        Arithmetic::Sub => math::subtract(lhs_arr, rhs_arr),
-                        Arithmetic::Mul => math::multiply(lhs_arr, rhs_arr),
-                        Arithmetic::Div => math::divide(lhs_arr, rhs_arr)
-                    })
-                },
-            )+
-            _ => unreachable!("should not reach this arithmetic op case - is_numeric_type should have denied already")
-        }
-    }
+    Ok((field, arr.to_array()?))
 }
 
 fn apply_arithmetic(
@@ -180,15 +353,76 @@ fn apply_arithmetic(
     op: &Arithmetic,
     lhs: &Column,
     rhs: &Column,
+    options: &QueryOptions,
 ) -> Result<(Field, ArrayRef), crate::Error> {
-    let (lhs_field, lhs_arr) = view.subview(&lhs).ok_or(Error::InvalidColumn {
-        column: Select::Column(lhs.clone()),
-    })?;
-    let (rhs_field, rhs_arr) = view.subview(&rhs).ok_or(Error::InvalidColumn {
-        column: Select::Column(rhs.clone()),
-    })?;
-
-    if lhs_arr.data_type() != rhs_arr.data_type() {
+    let (lhs_field, lhs_arr) = column_values(view, lhs)?;
+    let (rhs_field, rhs_arr) = column_values(view, rhs)?;
+
+    checked_arithmetic(op, &lhs_arr, &rhs_arr, options).map(|array_ref| {
+        let field = Field::new(
+            &format!("{}_{}_{}", rhs_field.name(), op.as_str(), lhs_field.name()),
+            array_ref.data_type().clone(),
+            lhs_field.is_nullable() && rhs_field.is_nullable(),
+        );
+        (field, array_ref)
+    })
+}
+
+/// Broadcast the literal to the length of the column and apply the operation, `scalar_first`
+/// places the literal on the left hand side
+fn apply_scalar_arithmetic(
+    view: &View,
+    op: &Arithmetic,
+    column: &Column,
+    value: &ScalarValue,
+    scalar_first: bool,
+    options: &QueryOptions,
+) -> Result<(Field, ArrayRef), crate::Error> {
+    let (field, arr) = column_values(view, column)?;
+
+    let scalar_arr = value.to_array(arr.len())?;
+
+    let (lhs_arr, rhs_arr, name) = if scalar_first {
+        let name = format!("{}_{}_{}", value, op.as_str(), field.name());
+        (scalar_arr, arr, name)
+    } else {
+        let name = format!("{}_{}_{}", field.name(), op.as_str(), value);
+        (arr, scalar_arr, name)
+    };
+
+    checked_arithmetic(op, &lhs_arr, &rhs_arr, options).map(|array_ref| {
+        let field = Field::new(&name, array_ref.data_type().clone(), field.is_nullable());
+        (field, array_ref)
+    })
+}
+
+fn apply_unary(
+    view: &View,
+    op: &Unary,
+    column: &Column,
+) -> Result<(Field, ArrayRef), crate::Error> {
+    let (field, arr) = column_values(view, column)?;
+
+    let array_ref = math::unary(&arr, op)?;
+    let field = Field::new(
+        &format!("{}({})", op.as_str(), field.name()),
+        array_ref.data_type().clone(),
+        field.is_nullable(),
+    );
+
+    Ok((field, array_ref))
+}
+
+fn checked_arithmetic(
+    op: &Arithmetic,
+    lhs_arr: &ArrayRef,
+    rhs_arr: &ArrayRef,
+    options: &QueryOptions,
+) -> Result<ArrayRef, crate::Error> {
+    // numeric arrays of different data types are promoted by the kernel
+    if lhs_arr.data_type() != rhs_arr.data_type()
+        && promote(lhs_arr.data_type(), rhs_arr.data_type()).is_none()
+    {
         return Err(Error::UnequalDataTypes {
             lhs: lhs_arr.data_type().clone(),
             rhs: rhs_arr.data_type().clone(),
@@ -204,29 +438,7 @@ fn apply_arithmetic(
         .into());
     }
 
-    apply_arithmetic!(
-        lhs_arr,
-        rhs_arr,
-        op,
-        [DataType::UInt8, dt::UInt8Type],
-        [DataType::UInt16, dt::UInt16Type],
-        [DataType::UInt32, dt::UInt32Type],
-        [DataType::UInt64, dt::UInt64Type],
-        [DataType::Int8, dt::Int8Type],
-        [DataType::Int16, dt::Int16Type],
-        [DataType::Int32, dt::Int32Type],
-        [DataType::Int64, dt::Int64Type],
-        [DataType::Float32, dt::Float32Type],
-        [DataType::Float64, dt::Float64Type]
-    )
-    .map(|array_ref| {
-        let field = Field::new(
-            &format!("{}_{}_{}", rhs_field.name(), op.as_str(), lhs_field.name()),
-            array_ref.data_type().clone(),
-            lhs_field.is_nullable() && rhs_field.is_nullable(),
-        );
-        (field, array_ref)
-    })
+    math::arithmetic(lhs_arr, rhs_arr, op, options.divide_by_zero)
 }
 
 #[cfg(test)]
@@ -250,4 +462,143 @@ mod test_select {
 
         assert_eq!(c_col.data(), array!(dt::UInt8Type, [2, 4, 6, 8, 10]).data());
     }
+
+    #[test]
+    fn it_selects_arithmetic_with_a_scalar() {
+        let view = view!(
+            ["price", dt::Float64Type, [10.0, 20.0, 40.0]],
+            ["qty", dt::Int32Type, [1, 2, 3]]
+        );
+
+        let res_view = select!(
+            view,
+            [
+                (Arithmetic::Mul, "price", ScalarValue::Float64(1.5)),
+                (Arithmetic::Sub, ScalarValue::Int32(10), "qty")
+            ]
+        )
+        .unwrap();
+
+        assert_eq!(res_view.field(&col!(0)).unwrap().name(), "price_*_1.5");
+
+        let price = res_view.column(&col!(0)).unwrap();
+        assert_eq!(
+            price.data(),
+            array!(dt::Float64Type, [15.0, 30.0, 60.0]).data()
+        );
+
+        let qty = res_view.column(&col!("10_-_qty")).unwrap();
+        assert_eq!(qty.data(), array!(dt::Int32Type, [9, 8, 7]).data());
+    }
+
+    #[test]
+    fn it_rejects_a_scalar_of_another_type() {
+        let view = view!(["qty", dt::Int32Type, [1, 2, 3]]);
+
+        let res = select!(view, [(Arithmetic::Add, "qty", ScalarValue::Boolean(true))]);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn it_promotes_mixed_numeric_types() {
+        let view = view!(
+            ["a", dt::UInt8Type, [1, 2, 3]],
+            ["b", dt::Int32Type, [-1, -2, -3]]
+        );
+
+        let res_view = select!(view, [sel!(sel!(Arithmetic::Add, "a", "b"), "c")]).unwrap();
+
+        let c = res_view.column(&col!("c")).unwrap();
+        assert_eq!(c.data(), array!(dt::Int32Type, [0, 0, 0]).data());
+    }
+
+    #[test]
+    fn it_selects_modulo_power_and_unary() {
+        let view = view!(
+            ["a", dt::Int32Type, [7, -7, 9]],
+            ["b", dt::Int32Type, [2, 2, 3]]
+        );
+
+        let res_view = select!(
+            view,
+            [
+                sel!(sel!(Arithmetic::Mod, "a", "b"), "mod"),
+                sel!(sel!(Arithmetic::IntDiv, "a", "b"), "div"),
+                sel!(sel!(Arithmetic::Pow, "a", "b"), "pow"),
+                (Unary::Abs, "a"),
+                (Unary::Neg, "b")
+            ]
+        )
+        .unwrap();
+
+        let m = res_view.column(&col!("mod")).unwrap();
+        assert_eq!(m.data(), array!(dt::Int32Type, [1, -1, 0]).data());
+
+        let d = res_view.column(&col!("div")).unwrap();
+        assert_eq!(d.data(), array!(dt::Int32Type, [3, -3, 3]).data());
+
+        let p = res_view.column(&col!("pow")).unwrap();
+        assert_eq!(p.data(), array!(dt::Int32Type, [49, 49, 729]).data());
+
+        let abs = res_view.column(&col!("abs(a)")).unwrap();
+        assert_eq!(abs.data(), array!(dt::Int32Type, [7, 7, 9]).data());
+
+        let neg = res_view.column(&col!("neg(b)")).unwrap();
+        assert_eq!(neg.data(), array!(dt::Int32Type, [-2, -2, -3]).data());
+    }
+
+    #[test]
+    fn it_handles_division_by_zero() {
+        use crate::ops::math::DivideByZero;
+        use arrow::array::Int32Array;
+
+        let view = view!(["a", dt::Int32Type, [6, 5]], ["b", dt::Int32Type, [3, 0]]);
+
+        let res = select(view.clone(), vec![sel!(Arithmetic::Div, "a", "b")]);
+        assert!(res.is_err());
+
+        let options = QueryOptions {
+            divide_by_zero: DivideByZero::Null,
+            ..QueryOptions::default()
+        };
+        let res_view =
+            select_with_options(view, vec![sel!(Arithmetic::Mod, "a", "b")], &options).unwrap();
+
+        let c = res_view.column(&col!(0)).unwrap();
+        let expected = Int32Array::from(vec![Some(0), None]);
+        assert_eq!(c.data(), expected.data());
+    }
+
+    #[test]
+    fn it_rejects_integer_overflow() {
+        let view = view!(
+            ["a", dt::Int32Type, [10, std::i32::MIN]],
+            ["b", dt::Int32Type, [10, -1]]
+        );
+
+        for op in vec![Arithmetic::Pow, Arithmetic::IntDiv] {
+            match select(view.clone(), vec![sel!(op, "a", "b")]) {
+                Err(crate::Error::Overflow { data_type, .. }) => {
+                    assert_eq!(data_type, DataType::Int32)
+                }
+                res => panic!("expected an overflow, got {:?}", res.map(|_| ())),
+            }
+        }
+    }
+
+    #[test]
+    fn it_selects_chunked_columns() {
+        let mut view = view!(["a", dt::Int32Type, [1, 2]], ["b", dt::Int32Type, [3, 4]]);
+        view.append(&view!(["a", dt::Int32Type, [5]], ["b", dt::Int32Type, [6]]))
+            .unwrap();
+
+        let res_view = select!(view, ["a", (Arithmetic::Add, "a", "b")]).unwrap();
+
+        // selecting the column keeps its chunks, the arithmetic is done over the whole column
+        assert_eq!(res_view.chunked_column(&col!("a")).unwrap().num_chunks(), 2);
+
+        let c = res_view.column(&col!(1)).unwrap();
+        let expected = array!(dt::Int32Type, [4, 6, 11]);
+        assert_eq!(c.data(), expected.data());
+    }
 }
@@ -16,6 +16,20 @@ pub fn is_numeric_type(dt: &DataType) -> bool {
     }
 }
 
+pub fn is_integer_type(dt: &DataType) -> bool {
+    match dt {
+        DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32
+        | DataType::UInt64 => true,
+        _ => false,
+    }
+}
+
 pub fn is_temporal_type(dt: &DataType) -> bool {
     match dt {
         DataType::Timestamp(_)
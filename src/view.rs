@@ -1,17 +1,29 @@
+use crate::chunked::{self, ChunkedArray};
 use crate::column::Column;
-use crate::DataFrame;
+use crate::datatype::ScalarValue;
+use crate::query::group;
+use crate::query::select::{Aggregate, Select};
+use crate::{DataFrame, Error};
 use arrow::{array::ArrayRef, datatypes::Field};
 use std::collections::HashMap;
 
 #[derive(Clone, Debug)]
 pub struct View {
-    columns: Vec<ArrayRef>,
+    columns: Vec<ChunkedArray>,
     fields: Vec<Field>,
     indexes: HashMap<String, usize>,
 }
 
 impl View {
     pub fn new(fields: Vec<Field>, columns: Vec<ArrayRef>) -> View {
+        View::from_chunks(
+            fields,
+            columns.into_iter().map(ChunkedArray::from).collect(),
+        )
+    }
+
+    /// Create the view from columns which may be split across multiple arrays
+    pub fn from_chunks(fields: Vec<Field>, columns: Vec<ChunkedArray>) -> View {
         let mut indexes = HashMap::with_capacity(fields.len());
 
         fields.iter().enumerate().for_each(|(i, f)| {
@@ -64,24 +76,40 @@ impl View {
         Some(&mut self.fields[index])
     }
 
-    pub fn column(&self, index: &Column) -> Option<ArrayRef> {
-        let index = self.get_index(index)?;
-        Some(self.columns[index].clone())
+    /// The column as a single array, chunked columns are concatenated. Use `chunked_column` to
+    /// read the chunks without copying them.
+    pub fn column(&self, index: &Column) -> Result<ArrayRef, Error> {
+        let (_, arr) = self.subview(index)?;
+        Ok(arr)
     }
 
     /// panics if index out of bounds
-    pub fn column_unchecked(&self, index: usize) -> ArrayRef {
-        self.columns[index].clone()
+    pub fn column_unchecked(&self, index: usize) -> Result<ArrayRef, Error> {
+        self.columns[index].to_array()
     }
 
-    pub fn subview(&self, index: &Column) -> Option<(Field, ArrayRef)> {
-        if let Some(position) = self.get_index(index) {
-            return Some((
-                self.fields[position].clone(),
-                self.columns[position].clone(),
-            ));
-        }
-        None
+    pub fn chunked_column(&self, index: &Column) -> Option<&ChunkedArray> {
+        let index = self.get_index(index)?;
+        Some(&self.columns[index])
+    }
+
+    pub fn subview(&self, index: &Column) -> Result<(Field, ArrayRef), Error> {
+        let (field, arr) = self
+            .chunked_subview(index)
+            .ok_or_else(|| Error::InvalidColumn {
+                column: index.clone(),
+            })?;
+
+        Ok((field, arr.to_array()?))
+    }
+
+    /// Same as `subview` but keeps the chunks of the column
+    pub fn chunked_subview(&self, index: &Column) -> Option<(Field, ChunkedArray)> {
+        let position = self.get_index(index)?;
+        Some((
+            self.fields[position].clone(),
+            self.columns[position].clone(),
+        ))
     }
 
     pub fn num_rows(&self) -> usize {
@@ -99,7 +127,241 @@ impl View {
         DataFrame::new(self)
     }
 
-    pub fn columns(&self) -> &Vec<ArrayRef> {
+    pub fn columns(&self) -> &Vec<ChunkedArray> {
         &self.columns
     }
+
+    pub fn value_scalar(&self, row: usize, col: usize) -> Result<ScalarValue<'_>, Error> {
+        let column = self.columns.get(col).ok_or(Error::IndexOutOfBounds {
+            index: col,
+            len: self.columns.len(),
+        })?;
+
+        column.value_scalar(row)
+    }
+
+    pub fn row(&self, index: usize) -> Result<Vec<ScalarValue<'_>>, Error> {
+        (0..self.columns.len())
+            .map(|col| self.value_scalar(index, col))
+            .collect()
+    }
+
+    /// Append the rows of the other view as new chunks without copying the existing values, the
+    /// views must have the same field names and data types
+    pub fn append(&mut self, other: &View) -> Result<(), Error> {
+        let same_fields = self.fields.len() == other.fields.len()
+            && self
+                .fields
+                .iter()
+                .zip(other.fields.iter())
+                .all(|(lhs, rhs)| lhs.name() == rhs.name() && lhs.data_type() == rhs.data_type());
+
+        if !same_fields {
+            return Err(chunked::Error::UnequalFields {
+                lhs: self.fields.clone(),
+                rhs: other.fields.clone(),
+            }
+            .into());
+        }
+
+        for (i, column) in self.columns.iter_mut().enumerate() {
+            column.extend(&other.columns[i])?;
+
+            if other.fields[i].is_nullable() && !self.fields[i].is_nullable() {
+                let field = &self.fields[i];
+                self.fields[i] = Field::new(field.name(), field.data_type().clone(), true);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Consolidate the chunks of every column into a single array
+    pub fn rechunk(&mut self) -> Result<(), Error> {
+        self.columns = self
+            .columns
+            .iter()
+            .map(|column| column.rechunk())
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(())
+    }
+
+    /// Reduce whole columns into a view with a single row, e.g. `(Aggregate::Sum, "price")`
+    /// produces the column `sum(price)`
+    pub fn agg(&self, aggregates: Vec<(Aggregate, Column)>) -> Result<View, Error> {
+        let columns = aggregates
+            .into_iter()
+            .map(|(agg, column)| Select::Aggregate(agg, column))
+            .collect();
+
+        group::group_by(self, vec![], columns)
+    }
+}
+
+#[cfg(test)]
+mod test_view {
+    use super::*;
+    use crate::{as_array, col, Field};
+    use arrow::array::{Array, Float64Array, Int32Array, Int64Array, UInt64Array};
+    use arrow::datatypes::DataType;
+    use std::sync::Arc;
+
+    #[test]
+    fn it_aggregates_whole_columns() {
+        let view = View::new(
+            vec![
+                Field::new("price", DataType::Int32, true),
+                Field::new("empty", DataType::Float64, true),
+            ],
+            vec![
+                Arc::new(Int32Array::from(vec![Some(3), None, Some(5), Some(1)])) as ArrayRef,
+                Arc::new(Float64Array::from(vec![None, None, None, None])) as ArrayRef,
+            ],
+        );
+
+        let res = view
+            .agg(vec![
+                (Aggregate::Sum, col!("price")),
+                (Aggregate::Count, col!("price")),
+                (Aggregate::Max, col!("price")),
+                (Aggregate::Avg, col!("empty")),
+                (Aggregate::Count, col!("empty")),
+            ])
+            .unwrap();
+
+        assert_eq!(res.num_rows(), 1);
+        assert_eq!(res.num_columns(), 5);
+
+        let sum = res.column(&col!("sum(price)")).unwrap();
+        assert_eq!(as_array!(sum, Int64Array).unwrap().value(0), 9);
+
+        let count = res.column(&col!(1)).unwrap();
+        assert_eq!(as_array!(count, UInt64Array).unwrap().value(0), 3);
+
+        let max = res.column(&col!("max(price)")).unwrap();
+        assert_eq!(as_array!(max, Int32Array).unwrap().value(0), 5);
+
+        let avg = res.column(&col!("avg(empty)")).unwrap();
+        assert!(avg.is_null(0));
+
+        let count = res.column(&col!(4)).unwrap();
+        assert_eq!(as_array!(count, UInt64Array).unwrap().value(0), 0);
+    }
+
+    #[test]
+    fn it_orders_nan_in_min_and_max() {
+        let nan = std::f64::NAN;
+
+        for values in [vec![nan, 2.0, 1.0], vec![2.0, 1.0, nan]].iter() {
+            let view = View::new(
+                vec![Field::new("reading", DataType::Float64, false)],
+                vec![Arc::new(Float64Array::from(values.clone())) as ArrayRef],
+            );
+
+            let res = view
+                .agg(vec![
+                    (Aggregate::Min, col!("reading")),
+                    (Aggregate::Max, col!("reading")),
+                ])
+                .unwrap();
+
+            let min = res.column(&col!(0)).unwrap();
+            assert_eq!(as_array!(min, Float64Array).unwrap().value(0), 1.0);
+
+            let max = res.column(&col!(1)).unwrap();
+            assert!(as_array!(max, Float64Array).unwrap().value(0).is_nan());
+        }
+    }
+
+    #[test]
+    fn it_rejects_a_sum_which_overflows() {
+        let view = View::new(
+            vec![Field::new("big", DataType::Int64, false)],
+            vec![Arc::new(Int64Array::from(vec![std::i64::MAX, 1])) as ArrayRef],
+        );
+
+        assert!(view.agg(vec![(Aggregate::Sum, col!("big"))]).is_err());
+    }
+
+    #[test]
+    fn it_appends_and_rechunks() {
+        let fields = vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("price", DataType::Float64, true),
+        ];
+
+        let mut view = View::new(
+            fields.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2])) as ArrayRef,
+                Arc::new(Float64Array::from(vec![Some(1.5), None])) as ArrayRef,
+            ],
+        );
+        let other = View::new(
+            fields,
+            vec![
+                Arc::new(Int32Array::from(vec![3])) as ArrayRef,
+                Arc::new(Float64Array::from(vec![Some(3.5)])) as ArrayRef,
+            ],
+        );
+
+        view.append(&other).unwrap();
+
+        assert_eq!(view.num_rows(), 3);
+        assert_eq!(view.chunked_column(&col!("id")).unwrap().num_chunks(), 2);
+        assert_eq!(
+            view.row(2).unwrap(),
+            vec![ScalarValue::Int32(3), ScalarValue::Float64(3.5)]
+        );
+        assert_eq!(view.value_scalar(1, 1).unwrap(), ScalarValue::Null);
+
+        let id = view.column(&col!("id")).unwrap();
+        assert_eq!(
+            as_array!(id, Int32Array).unwrap(),
+            &Int32Array::from(vec![1, 2, 3])
+        );
+
+        view.rechunk().unwrap();
+        assert_eq!(view.chunked_column(&col!("id")).unwrap().num_chunks(), 1);
+        assert_eq!(view.num_rows(), 3);
+    }
+
+    #[test]
+    fn it_rejects_appending_other_fields() {
+        let mut view = View::new(
+            vec![Field::new("id", DataType::Int32, false)],
+            vec![Arc::new(Int32Array::from(vec![1])) as ArrayRef],
+        );
+        let other = View::new(
+            vec![Field::new("id", DataType::Int64, false)],
+            vec![Arc::new(Int64Array::from(vec![1])) as ArrayRef],
+        );
+
+        assert!(view.append(&other).is_err());
+        assert_eq!(view.num_rows(), 1);
+    }
+
+    #[test]
+    fn it_aggregates_an_empty_view() {
+        let view = View::new(
+            vec![Field::new("price", DataType::Int32, true)],
+            vec![Arc::new(Int32Array::from(Vec::<i32>::new())) as ArrayRef],
+        );
+
+        let res = view
+            .agg(vec![
+                (Aggregate::Count, col!("price")),
+                (Aggregate::Sum, col!("price")),
+            ])
+            .unwrap();
+
+        assert_eq!(res.num_rows(), 1);
+
+        let count = res.column(&col!(0)).unwrap();
+        assert_eq!(as_array!(count, UInt64Array).unwrap().value(0), 0);
+
+        let sum = res.column(&col!(1)).unwrap();
+        assert!(sum.is_null(0));
+    }
 }